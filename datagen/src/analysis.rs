@@ -0,0 +1,353 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::Serialize;
+
+use crate::{
+    export::{outcome, placements, Outcome},
+    generator::Game,
+};
+
+/// what a set of stored games looks like
+#[derive(Serialize)]
+pub struct Report {
+    pub games: u64,
+    pub draws: u64,
+    pub lengths: LengthStats,
+    /// how every snake left the game, snakes that were still alive at the end count as survived
+    pub eliminations: BTreeMap<String, u64>,
+    /// results by position on the board, to spot a seat advantage
+    pub seats: Vec<SnakeStats>,
+    /// results by the player behind the snake
+    pub players: Vec<SnakeStats>,
+}
+
+/// distribution of game lengths in turns
+#[derive(Serialize)]
+pub struct LengthStats {
+    pub mean: f64,
+    pub min: u64,
+    pub p25: u64,
+    pub median: u64,
+    pub p75: u64,
+    pub max: u64,
+    pub bucket_size: u64,
+    /// number of games in every bucket, bucket i holds lengths from i * bucket_size
+    pub histogram: Vec<u64>,
+}
+
+#[derive(Serialize)]
+pub struct SnakeStats {
+    pub name: String,
+    pub games: u64,
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+    pub win_rate: f64,
+    pub mean_food_eaten: f64,
+    pub mean_final_length: f64,
+}
+
+/// running totals for one seat or player
+#[derive(Default)]
+struct Tally {
+    games: u64,
+    wins: u64,
+    draws: u64,
+    food_eaten: u64,
+    final_length: u64,
+}
+
+impl Tally {
+    fn add(&mut self, outcome: Outcome, food_eaten: u64, final_length: u64) {
+        self.games += 1;
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => {}
+        }
+        self.food_eaten += food_eaten;
+        self.final_length += final_length;
+    }
+
+    fn stats(&self, name: String) -> SnakeStats {
+        let games = self.games.max(1) as f64;
+        SnakeStats {
+            name,
+            games: self.games,
+            wins: self.wins,
+            draws: self.draws,
+            losses: self.games - self.wins - self.draws,
+            win_rate: self.wins as f64 / games,
+            mean_food_eaten: self.food_eaten as f64 / games,
+            mean_final_length: self.final_length as f64 / games,
+        }
+    }
+}
+
+/// value at the given fraction of the way through a sorted list
+fn percentile(sorted: &[u64], fraction: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[((sorted.len() - 1) as f64 * fraction).round() as usize]
+}
+
+/// replay every game and collect statistics over all of them
+pub fn analyze(games: &[Game], bucket_size: u64) -> Report {
+    let bucket_size = bucket_size.max(1);
+    let mut lengths = vec![];
+    let mut draws = 0;
+    let mut eliminations = BTreeMap::new();
+    let mut seats: Vec<Tally> = vec![];
+    let mut players: BTreeMap<String, Tally> = BTreeMap::new();
+
+    for game in games {
+        let boards = game.boards();
+        let final_board = boards.last().unwrap();
+        let placements = placements(final_board);
+        lengths.push(game.turns() as u64);
+        if game.winner().is_none() {
+            draws += 1;
+        }
+
+        for idx in 0..final_board.snakes.len() {
+            let snake = &final_board.snakes[idx];
+            let cause = match &snake.eliminated {
+                Some(elimination) => format!("{:?}", elimination.cause),
+                None => "Survived".to_string(),
+            };
+            *eliminations.entry(cause).or_insert(0) += 1;
+
+            // a snake ate whenever its head landed on food that was on the board the turn before
+            let food_eaten = boards
+                .windows(2)
+                .filter(|pair| {
+                    pair[0].snakes[idx].alive && pair[0].food.contains(&pair[1].snakes[idx].body[0])
+                })
+                .count() as u64;
+            let outcome = outcome(&placements, idx);
+            let final_length = snake.body.len() as u64;
+
+            if seats.len() <= idx {
+                seats.resize_with(idx + 1, Tally::default);
+            }
+            seats[idx].add(outcome, food_eaten, final_length);
+            let name = match game.player_name(idx) {
+                Some(name) => name.to_string(),
+                None => format!("snake {}", idx),
+            };
+            players
+                .entry(name)
+                .or_default()
+                .add(outcome, food_eaten, final_length);
+        }
+    }
+
+    lengths.sort_unstable();
+    let max = lengths.last().cloned().unwrap_or(0);
+    let mut histogram = vec![0; (max / bucket_size + 1) as usize];
+    for length in &lengths {
+        histogram[(length / bucket_size) as usize] += 1;
+    }
+    Report {
+        games: games.len() as u64,
+        draws,
+        lengths: LengthStats {
+            mean: lengths.iter().sum::<u64>() as f64 / lengths.len().max(1) as f64,
+            min: lengths.first().cloned().unwrap_or(0),
+            p25: percentile(&lengths, 0.25),
+            median: percentile(&lengths, 0.5),
+            p75: percentile(&lengths, 0.75),
+            max,
+            bucket_size,
+            histogram,
+        },
+        eliminations,
+        seats: seats
+            .iter()
+            .enumerate()
+            .map(|(idx, tally)| tally.stats(format!("seat {}", idx)))
+            .collect(),
+        players: players
+            .into_iter()
+            .map(|(name, tally)| tally.stats(name))
+            .collect(),
+    }
+}
+
+fn write_table(f: &mut fmt::Formatter<'_>, rows: &[SnakeStats]) -> fmt::Result {
+    let width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0).max(6);
+    writeln!(
+        f,
+        "  {:<width$} {:>6} {:>6} {:>6} {:>6} {:>7} {:>6} {:>7}",
+        "",
+        "games",
+        "wins",
+        "draws",
+        "losses",
+        "win %",
+        "food",
+        "length",
+        width = width
+    )?;
+    for row in rows {
+        writeln!(
+            f,
+            "  {:<width$} {:>6} {:>6} {:>6} {:>6} {:>6.1}% {:>6.1} {:>7.1}",
+            row.name,
+            row.games,
+            row.wins,
+            row.draws,
+            row.losses,
+            row.win_rate * 100.0,
+            row.mean_food_eaten,
+            row.mean_final_length,
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Games: {} ({} draws)", self.games, self.draws)?;
+        let l = &self.lengths;
+        writeln!(
+            f,
+            "Game length: mean {:.1}, min {}, p25 {}, median {}, p75 {}, max {}",
+            l.mean, l.min, l.p25, l.median, l.p75, l.max
+        )?;
+        let most = l.histogram.iter().cloned().max().unwrap_or(0).max(1);
+        for (bucket, count) in l.histogram.iter().enumerate() {
+            let from = bucket as u64 * l.bucket_size;
+            writeln!(
+                f,
+                "  {:>5}-{:<5} {:>6} {}",
+                from,
+                from + l.bucket_size - 1,
+                count,
+                "#".repeat((count * 40 / most) as usize)
+            )?;
+        }
+        writeln!(f, "Eliminations:")?;
+        let snakes = self.eliminations.values().sum::<u64>().max(1);
+        for (cause, count) in &self.eliminations {
+            writeln!(
+                f,
+                "  {:<14} {:>6} ({:.1}%)",
+                cause,
+                count,
+                *count as f64 * 100.0 / snakes as f64
+            )?;
+        }
+        writeln!(f, "Seats:")?;
+        write_table(f, &self.seats)?;
+        writeln!(f, "Players:")?;
+        write_table(f, &self.players)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Board, Coordinate, Direction, Snake},
+        generator::Turn,
+        ruleset::{Move, Ruleset},
+        standard::Standard,
+    };
+
+    fn snake(squares: &[(i32, i32)], id: usize) -> Snake {
+        let body = squares.iter().map(|(x, y)| Coordinate::new(*x, *y));
+        Snake::new(body.collect(), 100, id)
+    }
+
+    /// a two snake standard game on a 7x7 board with every move given up front
+    fn game(snakes: [&[(i32, i32)]; 2], food: &[(i32, i32)], turns: &[[Direction; 2]]) -> Game {
+        let ruleset = Standard::new(7, 7, 2, 0);
+        let mut board = Board {
+            food: food.iter().map(|(x, y)| Coordinate::new(*x, *y)).collect(),
+            snakes: vec![snake(snakes[0], 0), snake(snakes[1], 1)],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 7,
+            height: 7,
+            turn: 0,
+        };
+        let mut game = Game::new("test".to_string(), "standard".to_string(), board.clone());
+        for directions in turns {
+            let moves = (0..2)
+                .filter(|idx| board.snakes[*idx].alive)
+                .map(|idx| Move::new(directions[idx], idx))
+                .collect::<Vec<Move>>();
+            let micros = vec![0; moves.len()];
+            board = ruleset.apply_moves(board, &moves);
+            game.push_turn(Turn::new(moves, micros, &board, &board));
+        }
+        game
+    }
+
+    #[test]
+    fn counts_results_lengths_and_food() {
+        use Direction::*;
+        let games = [
+            // snake 0 eats while snake 1 leaves the board
+            game(
+                [&[(3, 3), (3, 2), (3, 1)], &[(0, 3), (1, 3), (2, 3)]],
+                &[(3, 4)],
+                &[[Up, Left]],
+            ),
+            // head to head between snakes of the same length
+            game(
+                [&[(2, 3), (1, 3), (0, 3)], &[(4, 3), (5, 3), (6, 3)]],
+                &[],
+                &[[Right, Left]],
+            ),
+            // snake 0 leaves the board on the second turn
+            game(
+                [&[(5, 0), (4, 0), (3, 0)], &[(3, 5), (3, 4), (3, 3)]],
+                &[],
+                &[[Right, Left], [Right, Left]],
+            ),
+        ];
+        let report = analyze(&games, 1);
+
+        assert_eq!((report.games, report.draws), (3, 1));
+        assert!((report.lengths.mean - 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!((report.lengths.min, report.lengths.max), (1, 2));
+        assert_eq!(report.lengths.median, 1);
+        assert_eq!(report.lengths.histogram, vec![0, 2, 1]);
+        let eliminations = report
+            .eliminations
+            .iter()
+            .map(|(cause, count)| (cause.as_str(), *count))
+            .collect::<Vec<(&str, u64)>>();
+        assert_eq!(
+            eliminations,
+            vec![("HeadToHead", 2), ("OutOfBounds", 2), ("Survived", 2)]
+        );
+
+        let first = &report.seats[0];
+        assert_eq!(
+            (first.games, first.wins, first.draws, first.losses),
+            (3, 1, 1, 1)
+        );
+        assert!((first.mean_food_eaten - 1.0 / 3.0).abs() < 1e-9);
+        assert!((first.mean_final_length - 10.0 / 3.0).abs() < 1e-9);
+        let second = &report.seats[1];
+        assert_eq!(
+            (second.games, second.wins, second.draws, second.losses),
+            (3, 1, 1, 1)
+        );
+        assert_eq!(second.mean_food_eaten, 0.0);
+        assert_eq!(second.mean_final_length, 3.0);
+        // without player names every snake is named after its seat
+        let names = report
+            .players
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["snake 0", "snake 1"]);
+        assert_eq!(report.players[0].wins, 1);
+    }
+}
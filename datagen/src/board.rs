@@ -1,8 +1,11 @@
-use std::iter::zip;
+use std::ops::Add;
 
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+use crate::rng::rng;
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Board {
     pub food: Vec<Coordinate>,
     pub snakes: Vec<Snake>,
@@ -10,9 +13,10 @@ pub struct Board {
     pub hazard_damage: i32,
     pub width: u32,
     pub height: u32,
+    pub turn: u32,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Direction {
     Up,
     Down,
@@ -20,7 +24,16 @@ pub enum Direction {
     Right,
 }
 
-#[derive(Clone, PartialEq, Eq, Copy)]
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+}
+
+#[derive(Clone, PartialEq, Eq, Copy, Debug, Serialize, Deserialize)]
 pub struct Coordinate {
     pub x: i32,
     pub y: i32,
@@ -30,15 +43,52 @@ impl Coordinate {
     pub fn new(x: i32, y: i32) -> Self {
         Self { x, y }
     }
+    pub fn manhattan(&self, other: &Coordinate) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+}
+
+impl Add<Direction> for Coordinate {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        let rhs = match rhs {
+            Direction::Up => Coordinate::new(0, 1),
+            Direction::Down => Coordinate::new(0, -1),
+            Direction::Left => Coordinate::new(-1, 0),
+            Direction::Right => Coordinate::new(1, 0),
+        };
+        Coordinate::new(self.x + rhs.x, self.y + rhs.y)
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Snake {
     pub id: usize,
     pub body: Vec<Coordinate>,
     pub health: i8,
     pub alive: bool,
+    pub eliminated: Option<Elimination>,
 }
+
+/// why and when a snake died
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Elimination {
+    pub cause: EliminationCause,
+    pub turn: u32,
+    /// idx of the snake responsible, if there was one
+    pub by: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EliminationCause {
+    OutOfHealth,
+    OutOfBounds,
+    SelfCollision,
+    Collision,
+    HeadToHead,
+}
+
 impl Snake {
     pub fn new(body: Vec<Coordinate>, health: i8, id: usize) -> Snake {
         Snake {
@@ -46,8 +96,13 @@ impl Snake {
             id,
             health,
             alive: true,
+            eliminated: None,
         }
     }
+    pub fn eliminate(&mut self, cause: EliminationCause, turn: u32, by: Option<usize>) {
+        self.alive = false;
+        self.eliminated = Some(Elimination { cause, turn, by });
+    }
 }
 impl Board {
     pub fn check_food_needed(&self, min_food: u32, spawn_chance: u8) -> u32 {
@@ -57,10 +112,10 @@ impl Board {
             return min_food - num_current_food;
         }
 
-        if spawn_chance > 0 && (100 - rand::random::<u8>() % 100) < spawn_chance {
+        if spawn_chance > 0 && (100 - rng().gen_range(0..100)) < spawn_chance {
             return 1;
         }
-        return 0;
+        0
     }
     pub fn place_food_randomly(&mut self, num_food: u32) {
         let unoccupied = self.get_unoccupied_points(false);
@@ -70,24 +125,30 @@ impl Board {
     pub fn get_unoccupied_points(&self, include_hazards: bool) -> Vec<Coordinate> {
         let mut out = vec![];
 
-        for x in zip(0..self.width, 0..self.height) {
-            let coord = Coordinate::new(x.0 as i32, x.1 as i32);
-            if self.food.contains(&coord)
-                || self
-                    .snakes
-                    .iter()
-                    .any(|x| x.alive && x.body.contains(&coord))
-                || (include_hazards && self.hazards.contains(&coord))
-            {
-                continue;
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let coord = Coordinate::new(x as i32, y as i32);
+                if self.food.contains(&coord)
+                    || self
+                        .snakes
+                        .iter()
+                        .any(|x| x.alive && x.body.contains(&coord))
+                    || (include_hazards && self.hazards.contains(&coord))
+                {
+                    continue;
+                }
+                out.push(coord);
             }
-            out.push(coord);
         }
         out
     }
     pub fn place_food_randomly_at_positions(&mut self, num_food: u32, unoccupied: Vec<Coordinate>) {
-        let mut rng = thread_rng();
-        for _ in 0..num_food {}
+        let mut rng = rng();
+        self.food.extend(
+            unoccupied
+                .choose_multiple(&mut rng, num_food as usize)
+                .cloned(),
+        );
     }
 
     pub fn create_default(width: u32, height: u32, num_snakes: u8) -> Board {
@@ -98,12 +159,26 @@ impl Board {
             hazard_damage: 0,
             width,
             height,
+            turn: 0,
         };
         board.place_snakes_automatic(num_snakes);
         board.place_food_automatic();
         board
     }
 
+    /// most snakes place_snakes_automatic can start on a board of this size
+    pub fn room_for_snakes(width: u32, height: u32) -> usize {
+        let center = Coordinate::new((width as i32 - 1) / 2, (height as i32 - 1) / 2);
+        let even = (0..width as i32)
+            .flat_map(|x| (0..height as i32).map(move |y| Coordinate::new(x, y)))
+            .filter(|c| (c.x + c.y) % 2 == 0 && *c != center)
+            .count();
+        if width == height && width < 7 {
+            even.min(8)
+        } else {
+            even
+        }
+    }
     pub fn place_snakes_automatic(&mut self, num_snakes: u8) {
         if self.square() {
             if num_snakes > 8 && self.width < 7 {
@@ -112,25 +187,75 @@ impl Board {
                     num_snakes, self.width, self.height
                 );
             }
-            if num_snakes <= 8 && self.width > 7 {
+            if num_snakes <= 8 && self.width >= 7 {
                 self.place_snakes_fixed(num_snakes);
                 return;
             }
             if self.width > 11 {
                 self.place_snakes_distributed(num_snakes);
+                return;
             }
         }
 
         self.place_snakes_randomly(num_snakes);
     }
     pub fn place_snakes_randomly(&mut self, num_snakes: u8) {
-        self.snakes = (0..num_snakes)
+        let mut snakes = (0..num_snakes)
             .map(|x| Snake::new(vec![], 100, x as usize))
             .collect::<Vec<Snake>>();
-        todo!()
+        // only use even squares so that snakes cant start next to each other,
+        // and leave the center free for food
+        let center = self.center();
+        let mut points = self
+            .get_unoccupied_points(false)
+            .into_iter()
+            .filter(|c| (c.x + c.y) % 2 == 0 && *c != center)
+            .collect::<Vec<Coordinate>>();
+        if points.len() < snakes.len() {
+            panic!(
+                "Not enough room for {} snakes on a {}x{} board",
+                num_snakes, self.width, self.height
+            );
+        }
+        points.shuffle(&mut rng());
+        for (idx, snake) in snakes.iter_mut().enumerate() {
+            snake.body = vec![points[idx]; 3];
+        }
+        self.snakes = snakes;
     }
     pub fn place_snakes_distributed(&mut self, num_snakes: u8) {
-        todo!()
+        let mut snakes = (0..num_snakes)
+            .map(|x| Snake::new(vec![], 100, x as usize))
+            .collect::<Vec<Snake>>();
+        // spread the snakes over an evenly spaced grid of even squares, leaving the center free for food
+        // a grid that only has enough squares with the center in it gets another row and column
+        let center = self.center();
+        let mut per_side = (num_snakes as f64).sqrt().ceil() as i32;
+        let mut points = vec![];
+        while points.len() < snakes.len() {
+            let spacing = self.width as i32 / (per_side + 1);
+            if spacing == 0 {
+                // too crowded for a grid, any even square will do
+                self.place_snakes_randomly(num_snakes);
+                return;
+            }
+            points.clear();
+            for i in 1..=per_side {
+                for j in 1..=per_side {
+                    let (x, y) = (i * spacing, j * spacing);
+                    let point = Coordinate::new(x - (x + y) % 2, y);
+                    if point != center && !points.contains(&point) {
+                        points.push(point);
+                    }
+                }
+            }
+            per_side += 1;
+        }
+        points.shuffle(&mut rng());
+        for (idx, snake) in snakes.iter_mut().enumerate() {
+            snake.body = vec![points[idx]; 3];
+        }
+        self.snakes = snakes;
     }
     pub fn place_snakes_fixed(&mut self, num_snakes: u8) {
         let mut snakes = (0..num_snakes)
@@ -154,12 +279,12 @@ impl Board {
             panic!("Too many snakes {} snakes", snakes.len());
         }
 
-        let mut rng = thread_rng();
+        let mut rng = rng();
         corner_points.shuffle(&mut rng);
         cardinal_points.shuffle(&mut rng);
 
         let mut start_points = vec![];
-        if rand::random() {
+        if rng.gen() {
             start_points.append(&mut corner_points);
             start_points.append(&mut cardinal_points);
         } else {
@@ -172,24 +297,24 @@ impl Board {
                 .map(|_| start_points[idx])
                 .collect::<Vec<Coordinate>>();
         }
+        self.snakes = snakes;
     }
     pub fn place_food_automatic(&mut self) {
-        if self.square() && self.width < 7 {
+        if self.square() && self.width >= 7 {
             self.place_food_fixed();
+            return;
         }
         self.place_food_random();
     }
     pub fn place_food_fixed(&mut self) {
-        let mut rng = thread_rng();
+        let mut rng = rng();
 
-        let center_coord =
-            Coordinate::new((self.width as i32 - 1) / 2, (self.height as i32 - 1) / 2);
+        let center_coord = self.center();
 
         let small = self.width * self.height < 11 * 11;
         if self.snakes.len() < 4 || !small {
-            for snake in &self.snakes {
-                let head = snake.body[0];
-
+            let heads = self.snakes.iter().map(|s| s.body[0]).collect::<Vec<_>>();
+            for head in heads {
                 let possible_food_locations = vec![
                     Coordinate::new(head.x - 1, head.y - 1),
                     Coordinate::new(head.x - 1, head.y + 1),
@@ -199,7 +324,7 @@ impl Board {
 
                 let mut available_food_locations = vec![];
                 for food in possible_food_locations {
-                    if food == center_coord {
+                    if food == center_coord || !self.in_bounds(&food) {
                         continue;
                     }
 
@@ -222,12 +347,11 @@ impl Board {
                     }
                     available_food_locations.push(food);
                 }
-                if available_food_locations.is_empty() {
-                    panic!("No room for food");
+                match available_food_locations.choose(&mut rng) {
+                    Some(food) => self.food.push(*food),
+                    // snakes packed in too tight for the usual squares still get a food somewhere
+                    None => self.place_food_randomly(1),
                 }
-
-                self.food
-                    .push(*available_food_locations.choose(&mut rng).unwrap());
             }
         }
 
@@ -242,15 +366,46 @@ impl Board {
             || (hazards && self.hazards.contains(point))
     }
     pub fn place_food_random(&mut self) {
-        todo!()
+        self.place_food_randomly(self.snakes.len() as u32);
+    }
+    pub fn in_bounds(&self, point: &Coordinate) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.width as i32 && point.y < self.height as i32
+    }
+    /// the middle square, rounded down on even sides
+    pub fn center(&self) -> Coordinate {
+        Coordinate::new((self.width as i32 - 1) / 2, (self.height as i32 - 1) / 2)
     }
     pub fn square(&self) -> bool {
         self.width == self.height
     }
     pub fn wrap(&mut self) {
         for x in &mut self.snakes {
-            x.body[0].x %= self.width as i32;
-            x.body[0].y %= self.height as i32;
+            x.body[0].x = x.body[0].x.rem_euclid(self.width as i32);
+            x.body[0].y = x.body[0].y.rem_euclid(self.height as i32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starting_boards_leave_the_center_for_food() {
+        for size in [7, 11, 13, 19] {
+            for snakes in (1..=16).chain([Board::room_for_snakes(size, size) as u8]) {
+                let board = Board::create_default(size, size, snakes);
+                assert_eq!(board.snakes.len(), snakes as usize);
+                assert!(
+                    board.snakes.iter().all(|s| s.body[0] != board.center()),
+                    "{} snakes on {}x{}",
+                    snakes,
+                    size,
+                    size
+                );
+                assert!(!board.food.is_empty());
+                assert!(board.food.iter().all(|f| board.in_bounds(f)));
+            }
         }
     }
 }
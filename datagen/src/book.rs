@@ -0,0 +1,173 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Coordinate, Direction},
+    export::{label_game, Outcome},
+    generator::{Game, GameInfo, GetMove},
+};
+
+/// how often a move was played from a position and how those games went
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
+pub struct MoveStats {
+    pub played: u32,
+    pub wins: u32,
+    pub draws: u32,
+}
+
+impl MoveStats {
+    /// points scored per game, counting a draw as half a win
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.played.max(1) as f64
+    }
+}
+
+/// moves played in the opening of stored games, keyed by the position they were played from
+#[derive(Serialize, Deserialize)]
+pub struct OpeningBook {
+    /// only positions before this turn are in the book
+    pub turns: u32,
+    /// stats for every move, in the order of `Direction::ALL`
+    pub positions: HashMap<String, [MoveStats; 4]>,
+}
+
+fn write_coordinates(key: &mut String, coordinates: &[Coordinate]) {
+    for c in coordinates {
+        key.push_str(&format!("{},{} ", c.x, c.y));
+    }
+    key.push('|');
+}
+
+/// a key for the board as seen by the snake at you_idx
+/// the other snakes and the food are sorted so the order they are stored in doesnt matter
+pub fn position_key(board: &Board, you_idx: usize) -> String {
+    let mut key = format!("{}x{}|", board.width, board.height);
+    write_coordinates(&mut key, &board.snakes[you_idx].body);
+    let mut others = board
+        .snakes
+        .iter()
+        .enumerate()
+        .filter(|(idx, snake)| *idx != you_idx && snake.alive)
+        .map(|(_, snake)| {
+            let mut body = String::new();
+            write_coordinates(&mut body, &snake.body);
+            body
+        })
+        .collect::<Vec<String>>();
+    others.sort();
+    others.iter().for_each(|body| key.push_str(body));
+    let mut food = board.food.clone();
+    food.sort_by_key(|c| (c.x, c.y));
+    write_coordinates(&mut key, &food);
+    key
+}
+
+impl OpeningBook {
+    /// an empty book covering the first `turns` turns of a game
+    pub fn new(turns: u32) -> Self {
+        Self {
+            turns,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// add every move every snake made in the opening of the game
+    pub fn add_game(&mut self, game: &Game) {
+        for position in label_game(game) {
+            if position.turn >= self.turns {
+                continue;
+            }
+            let direction = match position.moves[0] {
+                Some(direction) => direction,
+                None => continue,
+            };
+            let entry = self
+                .positions
+                .entry(position_key(&position.board, 0))
+                .or_default();
+            let stats = &mut entry[Direction::ALL.iter().position(|d| *d == direction).unwrap()];
+            stats.played += 1;
+            match position.outcome {
+                Outcome::Win => stats.wins += 1,
+                Outcome::Draw => stats.draws += 1,
+                Outcome::Loss => {}
+            }
+        }
+    }
+
+    /// stats for every move from this position, none if it isnt in the book
+    pub fn lookup(&self, board: &Board, you_idx: usize) -> Option<&[MoveStats; 4]> {
+        if board.turn >= self.turns {
+            return None;
+        }
+        self.positions.get(&position_key(board, you_idx))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        serde_json::to_writer(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+/// plays the best scoring book move while it is in the book, and asks the inner player after that
+#[derive(Clone)]
+pub struct BookPlayer {
+    book: Arc<OpeningBook>,
+    inner: Box<dyn GetMove>,
+}
+
+impl BookPlayer {
+    pub fn new(book: Arc<OpeningBook>, inner: Box<dyn GetMove>) -> Self {
+        Self { book, inner }
+    }
+
+    fn book_move(&self, board: &Board, you_idx: usize) -> Option<Direction> {
+        let stats = self.book.lookup(board, you_idx)?;
+        Direction::ALL
+            .iter()
+            .zip(stats.iter())
+            .filter(|(_, s)| s.played > 0)
+            .max_by(|(_, a), (_, b)| {
+                a.score()
+                    .total_cmp(&b.score())
+                    .then(a.played.cmp(&b.played))
+            })
+            .map(|(direction, _)| *direction)
+    }
+}
+
+impl GetMove for BookPlayer {
+    fn start(&mut self, game: &GameInfo, board: &Board, you_idx: usize) {
+        self.inner.start(game, board, you_idx);
+    }
+    fn scores(&mut self, board: &Board, you_idx: usize) -> Option<[f64; 4]> {
+        // in the book get_move plays the book move, after that the inner player's scores count
+        match self.book_move(board, you_idx) {
+            Some(_) => None,
+            None => self.inner.scores(board, you_idx),
+        }
+    }
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        match self.book_move(board, you_idx) {
+            Some(direction) => direction,
+            None => self.inner.get_move(board, you_idx),
+        }
+    }
+    fn end(&mut self, game: &GameInfo, board: &Board, you_idx: usize) {
+        self.inner.end(game, board, you_idx);
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
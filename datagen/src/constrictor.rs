@@ -0,0 +1,90 @@
+use crate::{
+    board::Board,
+    ruleset::{Move, Ruleset},
+    standard::{
+        damage_hazards, eliminate_snakes, feed_snakes, move_snakes, reduce_snake_health, Standard,
+    },
+};
+
+/// no food, every snake grows by one and is fully healed every turn
+#[derive(Clone)]
+pub struct Constrictor {
+    standard: Standard,
+}
+
+impl Ruleset for Constrictor {
+    fn name(&self) -> String {
+        "constrictor".to_string()
+    }
+    fn generate_board(&self) -> Board {
+        let mut board = self.standard.generate_board();
+        board.food.clear();
+        board
+    }
+
+    fn apply_moves(&self, mut board: Board, moves: &[Move]) -> Board {
+        move_snakes(&mut board, moves);
+        reduce_snake_health(&mut board);
+        damage_hazards(&mut board);
+        feed_snakes(&mut board);
+        eliminate_snakes(&mut board);
+        // always grow
+        for snake in &mut board.snakes {
+            if !snake.alive {
+                continue;
+            }
+            snake.health = 100;
+            snake.body.push(*snake.body.last().unwrap());
+        }
+        board.turn += 1;
+        board
+    }
+
+    // nothing ever spawns in constrictor
+    fn spawn(&self, _board: &mut Board) {}
+
+    fn game_over(&self, board: &Board) -> bool {
+        self.standard.game_over(board)
+    }
+}
+
+impl Constrictor {
+    pub fn new(width: u32, height: u32, num_players: u8) -> Constrictor {
+        Constrictor {
+            standard: Standard::new(width, height, num_players, 0).with_minimum_food(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Coordinate, Direction, Snake};
+
+    #[test]
+    fn grows_every_turn_without_food() {
+        let ruleset = Constrictor::new(11, 11, 2);
+        assert!(ruleset.generate_board().food.is_empty());
+        let snake = |x, id| Snake::new(vec![Coordinate::new(x, 1); 3], 50, id);
+        let mut board = Board {
+            food: vec![],
+            snakes: vec![snake(1, 0), snake(9, 1)],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 0,
+        };
+        for turn in 1..=5 {
+            let moves = [Move::new(Direction::Up, 0), Move::new(Direction::Up, 1)];
+            board = ruleset.apply_moves(board, &moves);
+            ruleset.spawn(&mut board);
+            assert!(board.food.is_empty());
+            for snake in &board.snakes {
+                assert!(snake.alive);
+                assert_eq!(snake.health, 100);
+                assert_eq!(snake.body.len(), 3 + turn);
+            }
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Direction},
+    generator::Game,
+};
+
+/// how the game ended for one snake
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Outcome {
+    /// outlasted everyone
+    Win,
+    /// someone outlasted it
+    Loss,
+    /// died on the same turn as everyone that was left
+    Draw,
+}
+
+/// a position labeled with how the game went on to end, seen from one snake
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LabeledPosition {
+    pub game_id: String,
+    pub turn: u32,
+    /// idx of the snake this position is seen from in the original game
+    pub perspective: usize,
+    /// the board with the perspective snake moved to the front, the others keep their order
+    pub board: Board,
+    /// final placement of each snake in `board.snakes`, 1 is the winner and ties share a placement
+    pub placements: Vec<u8>,
+    /// how the game ended for the perspective snake
+    pub outcome: Outcome,
+    pub turns_until_end: u32,
+    /// the move each snake in `board.snakes` played from here, none if it was already dead
+    pub moves: Vec<Option<Direction>>,
+}
+
+/// final placement of every snake, going by the turn it was eliminated on
+pub fn placements(final_board: &Board) -> Vec<u8> {
+    // snakes that survived the game outlast everyone who got eliminated
+    let survived_until = |idx: usize| {
+        final_board.snakes[idx]
+            .eliminated
+            .map(|e| e.turn)
+            .unwrap_or(u32::MAX)
+    };
+    (0..final_board.snakes.len())
+        .map(|idx| {
+            let outlasted_by = (0..final_board.snakes.len())
+                .filter(|other| survived_until(*other) > survived_until(idx))
+                .count();
+            outlasted_by as u8 + 1
+        })
+        .collect()
+}
+
+/// how the game ended for the snake at idx, given everyone's placements
+pub fn outcome(placements: &[u8], idx: usize) -> Outcome {
+    if placements[idx] != 1 {
+        Outcome::Loss
+    } else if placements.iter().filter(|p| **p == 1).count() > 1 {
+        Outcome::Draw
+    } else {
+        Outcome::Win
+    }
+}
+
+/// every position of the game from the point of view of every snake still alive in it
+pub fn label_game(game: &Game) -> Vec<LabeledPosition> {
+    let boards = game.boards();
+    let final_board = boards.last().unwrap();
+    let placements = placements(final_board);
+    let end = final_board.turn;
+    let mut out = vec![];
+    for (board, turn) in boards.iter().zip(game.history()) {
+        let mut moves = vec![None; board.snakes.len()];
+        for m in &turn.moves {
+            moves[m.idx] = Some(m.direction);
+        }
+        for perspective in 0..board.snakes.len() {
+            if !board.snakes[perspective].alive {
+                continue;
+            }
+            // the perspective snake first, then everyone else in their original order
+            let order = std::iter::once(perspective)
+                .chain((0..board.snakes.len()).filter(|idx| *idx != perspective))
+                .collect::<Vec<usize>>();
+            let mut rotated = board.clone();
+            rotated.snakes = order.iter().map(|idx| board.snakes[*idx].clone()).collect();
+            out.push(LabeledPosition {
+                game_id: game.game_id().to_string(),
+                turn: board.turn,
+                perspective,
+                board: rotated,
+                placements: order.iter().map(|idx| placements[*idx]).collect(),
+                outcome: outcome(&placements, perspective),
+                turns_until_end: end - board.turn,
+                moves: order.iter().map(|idx| moves[*idx]).collect(),
+            });
+        }
+    }
+    out
+}
+
+/// write one json value per line
+pub fn write_json_lines<T: Serialize>(path: &Path, items: &[T]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for item in items {
+        serde_json::to_writer(&mut writer, item)?;
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
+
+/// read a file written by `write_json_lines`
+pub fn read_json_lines<T: DeserializeOwned>(path: &Path) -> io::Result<Vec<T>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut out = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        out.push(serde_json::from_str(&line)?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Coordinate, EliminationCause, Snake},
+        generator::Turn,
+        ruleset::{Move, Ruleset},
+        standard::Standard,
+    };
+
+    fn snake(squares: &[(i32, i32)], id: usize) -> Snake {
+        let body = squares.iter().map(|(x, y)| Coordinate::new(*x, *y));
+        Snake::new(body.collect(), 100, id)
+    }
+
+    /// an empty 7x7 board
+    fn board(snakes: Vec<Snake>) -> Board {
+        Board {
+            food: vec![],
+            snakes,
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 7,
+            height: 7,
+            turn: 0,
+        }
+    }
+
+    /// a standard game on an empty 7x7 board with every move given up front, alive snakes in order
+    fn game(snakes: Vec<Snake>, turns: &[&[Direction]]) -> Game {
+        let ruleset = Standard::new(7, 7, snakes.len() as u8, 0);
+        let mut board = board(snakes);
+        let mut game = Game::new("test".to_string(), "standard".to_string(), board.clone());
+        for directions in turns {
+            let alive = (0..board.snakes.len()).filter(|idx| board.snakes[*idx].alive);
+            let moves = alive
+                .zip(directions.iter())
+                .map(|(idx, direction)| Move::new(*direction, idx))
+                .collect::<Vec<Move>>();
+            let micros = vec![0; moves.len()];
+            board = ruleset.apply_moves(board, &moves);
+            game.push_turn(Turn::new(moves, micros, &board, &board));
+        }
+        game
+    }
+
+    #[test]
+    fn placements_follow_elimination_turns() {
+        let mut board = board((0..4).map(|id| snake(&[(id as i32, 0)], id)).collect());
+        board.snakes[0].eliminate(EliminationCause::OutOfHealth, 3, None);
+        board.snakes[1].eliminate(EliminationCause::HeadToHead, 5, Some(2));
+        board.snakes[2].eliminate(EliminationCause::HeadToHead, 5, Some(1));
+        // still alive outlasts everyone, and the two that went out together share a place
+        let placements = placements(&board);
+        assert_eq!(placements, vec![4, 2, 2, 1]);
+        assert_eq!(outcome(&placements, 3), Outcome::Win);
+        assert_eq!(outcome(&placements, 1), Outcome::Loss);
+        assert_eq!(outcome(&placements, 0), Outcome::Loss);
+    }
+
+    #[test]
+    fn head_to_head_is_a_draw() {
+        let game = game(
+            vec![
+                snake(&[(2, 3), (1, 3), (0, 3)], 0),
+                snake(&[(4, 3), (5, 3), (6, 3)], 1),
+            ],
+            &[&[Direction::Right, Direction::Left]],
+        );
+        assert_eq!(game.winner(), None);
+        assert_eq!(placements(game.final_board()), vec![1, 1]);
+
+        let labeled = label_game(&game);
+        assert_eq!(labeled.len(), 2);
+        for position in &labeled {
+            assert_eq!(position.outcome, Outcome::Draw);
+            assert_eq!(position.placements, vec![1, 1]);
+            assert_eq!(position.turns_until_end, 1);
+        }
+    }
+
+    #[test]
+    fn every_snake_gets_its_own_view() {
+        // snake 2 leaves the board straight away, snake 1 a turn later and snake 0 outlasts them
+        let game = game(
+            vec![
+                snake(&[(4, 4), (4, 3), (4, 2)], 0),
+                snake(&[(5, 0), (4, 0), (3, 0)], 1),
+                snake(&[(0, 3), (1, 3), (2, 3)], 2),
+            ],
+            &[
+                &[Direction::Up, Direction::Right, Direction::Left],
+                &[Direction::Up, Direction::Right],
+            ],
+        );
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!(placements(game.final_board()), vec![1, 2, 3]);
+
+        let labeled = label_game(&game);
+        // all three on the first turn, then the two still alive
+        let views = labeled
+            .iter()
+            .map(|p| (p.turn, p.perspective, p.turns_until_end))
+            .collect::<Vec<(u32, usize, u32)>>();
+        assert_eq!(
+            views,
+            vec![(0, 0, 2), (0, 1, 2), (0, 2, 2), (1, 0, 1), (1, 1, 1)]
+        );
+
+        let view = &labeled[1];
+        let ids = view
+            .board
+            .snakes
+            .iter()
+            .map(|s| s.id)
+            .collect::<Vec<usize>>();
+        assert_eq!(ids, vec![1, 0, 2]);
+        assert_eq!(view.placements, vec![2, 1, 3]);
+        assert_eq!(view.outcome, Outcome::Loss);
+        assert_eq!(
+            view.moves,
+            vec![
+                Some(Direction::Right),
+                Some(Direction::Up),
+                Some(Direction::Left)
+            ]
+        );
+        // snake 2 is already out on the second turn
+        let view = &labeled[4];
+        assert_eq!(
+            view.moves,
+            vec![Some(Direction::Right), Some(Direction::Up), None]
+        );
+        assert_eq!(labeled[3].outcome, Outcome::Win);
+    }
+}
@@ -1,19 +1,236 @@
+use std::{
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use indicatif::ProgressBar;
+use rand::{seq::SliceRandom, Rng};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    board::{Board, Direction},
-    ruleset::{Move, Ruleset},
+    board::{Board, Coordinate, Direction},
+    players::random_safe_move,
+    rng,
+    ruleset::{self, Move, Ruleset},
+    validate::validate,
 };
 
+/// a played game, stored as the starting board and the moves made each turn
+#[derive(Serialize, Deserialize)]
 pub struct Game {
-    boards: Vec<Board>,
     game_id: String,
     ruleset_name: String,
+    initial: Board,
+    history: Vec<Turn>,
+    /// name of the player behind every snake, empty if they werent given
+    #[serde(default)]
+    players: Vec<String>,
+    /// the time limit each move was played under, in milliseconds
+    #[serde(default)]
+    timeout_millis: Option<u64>,
+    /// how long every snake took to pick its moves
+    #[serde(default)]
+    latency: Vec<LatencyStats>,
+    /// the board the game ended on, kept after the first replay since every result needs it
+    #[serde(skip)]
+    final_board: OnceLock<Board>,
+}
+
+/// everything that happened in a single turn
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Turn {
+    /// the move made by every snake that was alive at the start of the turn
+    pub moves: Vec<Move>,
+    /// how long each of those moves took to pick, in microseconds
+    pub decision_micros: Vec<u64>,
+    /// food that randomly spawned at the end of the turn
+    pub spawned_food: Vec<Coordinate>,
+    /// the hazards at the end of the turn, only stored when they changed
+    pub hazards: Option<Vec<Coordinate>>,
+}
+
+/// how long one snake took to pick its moves over a game
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
+pub struct LatencyStats {
+    pub moves: u32,
+    pub mean_micros: u64,
+    pub max_micros: u64,
+    /// moves that went over the time limit and were replaced by the previous move
+    pub timeouts: u32,
+}
+
+impl LatencyStats {
+    fn record(&mut self, micros: u64, timed_out: bool) {
+        // running mean so a long game cant overflow the total
+        let total = self.mean_micros * self.moves as u64 + micros;
+        self.moves += 1;
+        self.mean_micros = total / self.moves as u64;
+        self.max_micros = self.max_micros.max(micros);
+        if timed_out {
+            self.timeouts += 1;
+        }
+    }
+}
+
+impl Game {
+    /// an empty record starting from the given board
+    pub fn new(game_id: String, ruleset_name: String, initial: Board) -> Self {
+        Self {
+            game_id,
+            ruleset_name,
+            initial,
+            history: vec![],
+            players: vec![],
+            timeout_millis: None,
+            latency: vec![],
+            final_board: OnceLock::new(),
+        }
+    }
+
+    /// add the next turn to the end of the game
+    pub fn push_turn(&mut self, turn: Turn) {
+        self.history.push(turn);
+        self.final_board.take();
+    }
+
+    pub fn game_id(&self) -> &str {
+        &self.game_id
+    }
+
+    pub fn ruleset_name(&self) -> &str {
+        &self.ruleset_name
+    }
+
+    pub fn history(&self) -> &[Turn] {
+        &self.history
+    }
+
+    /// name of the player that controlled the snake, if the generator was told
+    pub fn player_name(&self, idx: usize) -> Option<&str> {
+        self.players.get(idx).map(|name| name.as_str())
+    }
+
+    /// move timings of every snake, indexed like the snakes on the board
+    pub fn latency(&self) -> &[LatencyStats] {
+        &self.latency
+    }
+
+    /// number of turns that were played
+    pub fn turns(&self) -> usize {
+        self.history.len()
+    }
+
+    /// a ruleset that can replay this game, none if the ruleset name is unknown
+    fn replay_ruleset(&self) -> Option<Box<dyn Ruleset + Send + Sync>> {
+        // the deterministic part of a turn doesnt depend on the food settings
+        ruleset::from_name(
+            &self.ruleset_name,
+            self.initial.width,
+            self.initial.height,
+            self.initial.snakes.len() as u8,
+            &ruleset::Settings {
+                food_spawn_chance: 0,
+                minimum_food: 0,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// every board of the game, from the initial board to the final one
+    pub fn boards(&self) -> Vec<Board> {
+        let ruleset = self
+            .replay_ruleset()
+            .unwrap_or_else(|| panic!("Cant replay ruleset {}", self.ruleset_name));
+        let mut boards = vec![self.initial.clone()];
+        for turn in &self.history {
+            let board = turn.replay(&ruleset, boards.last().unwrap().clone());
+            boards.push(board);
+        }
+        let _ = self.final_board.set(boards.last().unwrap().clone());
+        boards
+    }
+
+    /// the board the game ended on, only replayed the first time it is asked for
+    pub fn final_board(&self) -> &Board {
+        self.final_board.get_or_init(|| {
+            let ruleset = self
+                .replay_ruleset()
+                .unwrap_or_else(|| panic!("Cant replay ruleset {}", self.ruleset_name));
+            self.history
+                .iter()
+                .fold(self.initial.clone(), |board, t| t.replay(&ruleset, board))
+        })
+    }
+
+    /// idx of the last snake standing, none if everyone died on the same turn
+    pub fn winner(&self) -> Option<usize> {
+        let mut alive = self.final_board().snakes.iter().filter(|s| s.alive);
+        match (alive.next(), alive.next()) {
+            (Some(snake), None) => Some(snake.id),
+            _ => None,
+        }
+    }
+}
+
+impl Turn {
+    /// record a turn from the board before and after the random spawns
+    pub fn new(
+        moves: Vec<Move>,
+        decision_micros: Vec<u64>,
+        applied: &Board,
+        spawned: &Board,
+    ) -> Self {
+        Self {
+            moves,
+            decision_micros,
+            spawned_food: spawned
+                .food
+                .iter()
+                .filter(|f| !applied.food.contains(f))
+                .cloned()
+                .collect(),
+            hazards: if spawned.hazards != applied.hazards {
+                Some(spawned.hazards.clone())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// play this turn on the board it started from
+    pub fn replay<R: Ruleset>(&self, ruleset: &R, board: Board) -> Board {
+        let mut board = ruleset.apply_moves(board, &self.moves);
+        board.food.extend(self.spawned_food.iter().cloned());
+        if let Some(hazards) = &self.hazards {
+            board.hazards = hazards.clone();
+        }
+        board
+    }
+}
+
+/// information about the game being played that doesnt live on the board
+#[derive(Clone)]
+pub struct GameInfo {
+    pub game_id: String,
+    pub ruleset_name: String,
+    pub map_name: String,
+    /// how long a snake has to pick each move, none if there is no limit
+    pub timeout: Option<Duration>,
 }
 
 pub trait GetMove: Send + Sync {
+    /// called once before the first move of every game
+    fn start(&mut self, _game: &GameInfo, _board: &Board, _you_idx: usize) {}
     fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction;
+    /// how good each move in `Direction::ALL` is, in whatever units the player likes, higher is better
+    /// none if the player doesnt score its moves
+    fn scores(&mut self, _board: &Board, _you_idx: usize) -> Option<[f64; 4]> {
+        None
+    }
+    /// called once after the game is over with the final board
+    fn end(&mut self, _game: &GameInfo, _board: &Board, _you_idx: usize) {}
     fn clone_dyn(&self) -> Box<dyn GetMove>;
 }
 
@@ -29,6 +246,14 @@ where
 {
     players: Vec<Box<dyn GetMove>>,
     ruleset: R,
+    seed: Option<u64>,
+    timeout: Option<Duration>,
+    names: Vec<String>,
+    epsilon: f64,
+    temperature: Option<f64>,
+    random_plies: u32,
+    start_positions: Vec<Board>,
+    progress: Option<ProgressBar>,
 }
 impl<R> Generator<R>
 where
@@ -36,31 +261,386 @@ where
 {
     // make a new game from the players and
     pub fn new(players: Vec<Box<dyn GetMove>>, ruleset: R) -> Self {
-        Self { players, ruleset }
+        Self {
+            players,
+            ruleset,
+            seed: None,
+            timeout: None,
+            names: vec![],
+            epsilon: 0.0,
+            temperature: None,
+            random_plies: 0,
+            start_positions: vec![],
+            progress: None,
+        }
+    }
+
+    /// make the games reproducible, game n is played with seed + n
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// store these names for the players in every game, in the same order as the players
+    pub fn with_player_names(mut self, names: Vec<String>) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// moves that take longer than this are thrown away and the snake repeats its last move,
+    /// like the official engine does. the move is only thrown away once the player returns it,
+    /// so a slow local player still holds the game up, http players stop waiting at the limit
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// replace each move with a random safe one with this probability
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.clamp(0.0, 1.0);
+        self
+    }
+
+    /// sample moves from a softmax over the player's scores instead of taking its move,
+    /// for players that score their moves. the temperature is in the units of the scores
+    pub fn with_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// every snake plays random safe moves for the first plies turns of every game
+    pub fn with_random_plies(mut self, plies: u32) -> Self {
+        self.random_plies = plies;
+        self
+    }
+
+    /// start every game from one of these boards instead of a fresh one
+    /// boards that dont have a snake for every player or are already over are left out
+    pub fn with_start_positions(mut self, boards: Vec<Board>) -> Self {
+        self.start_positions = boards
+            .into_iter()
+            .filter(|b| b.snakes.len() == self.players.len() && !self.ruleset.game_over(b))
+            .collect();
+        self
+    }
+
+    /// tick this bar every time a game finishes
+    pub fn with_progress_bar(mut self, bar: ProgressBar) -> Self {
+        self.progress = Some(bar);
+        self
     }
 
     pub fn generate_data(&mut self, total_games: u64) -> Vec<Game> {
-        let mut games = vec![];
-        for _ in 0..total_games {
-            games.push(Game {
-                boards: vec![self.ruleset.generate_board()],
-                game_id: Uuid::new_v4().to_string(),
-                ruleset_name: self.ruleset.name(),
-            });
-        }
-        games.par_iter_mut().for_each(|x| {
-            let mut players = self.players.clone();
-            let mut board = x.boards[0].clone();
-            while !self.ruleset.game_over(&board) {
-                let moves = players
-                    .iter_mut()
-                    .enumerate()
-                    .map(|(idx, player)| Move::new(player.get_move(&board, idx), idx))
-                    .collect::<Vec<Move>>();
-                board = self.ruleset.step_board(board, moves);
-                x.boards.push(board.clone());
+        (0..total_games)
+            .into_par_iter()
+            .map(|game_idx| {
+                // each game runs start to finish on one thread, so seeding here covers all of it
+                if let Some(seed) = self.seed {
+                    rng::seed(seed.wrapping_add(game_idx));
+                }
+                let game = self.play_game();
+                if let Some(bar) = &self.progress {
+                    bar.inc(1);
+                }
+                game
+            })
+            .collect()
+    }
+
+    fn play_game(&self) -> Game {
+        let mut players = self.players.clone();
+        let mut board = match self.start_positions.choose(&mut rng::rng()) {
+            Some(start) => start.clone(),
+            None => self.ruleset.generate_board(),
+        };
+        let mut game = Game::new(
+            Uuid::new_v4().to_string(),
+            self.ruleset.name(),
+            board.clone(),
+        );
+        game.players = self.names.clone();
+        game.timeout_millis = self.timeout.map(|t| t.as_millis() as u64);
+        game.latency = vec![LatencyStats::default(); players.len()];
+        let info = GameInfo {
+            game_id: game.game_id.clone(),
+            ruleset_name: game.ruleset_name.clone(),
+            map_name: self.ruleset.map_name(),
+            timeout: self.timeout,
+        };
+        let mut last_moves = vec![None; players.len()];
+        // catch rule bugs on the turn they happen instead of in the training data
+        let wrapped = self.ruleset.name() == "wrapped";
+        if cfg!(debug_assertions) {
+            check_board(&board, wrapped);
+        }
+        for (idx, player) in players.iter_mut().enumerate() {
+            player.start(&info, &board, idx);
+        }
+        while !self.ruleset.game_over(&board) {
+            let mut moves = vec![];
+            let mut decision_micros = vec![];
+            for (idx, player) in players.iter_mut().enumerate() {
+                if !board.snakes[idx].alive {
+                    continue;
+                }
+                let start = Instant::now();
+                let scores = match self.temperature {
+                    Some(_) => player.scores(&board, idx),
+                    None => None,
+                };
+                let mut direction = match scores {
+                    Some(scores) => sample_softmax(&scores, self.temperature.unwrap()),
+                    None => player.get_move(&board, idx),
+                };
+                let elapsed = start.elapsed();
+                let timed_out = self.timeout.is_some_and(|limit| elapsed > limit);
+                if timed_out {
+                    // the engine repeats the last move, or goes up on the first turn
+                    direction = last_moves[idx].unwrap_or(Direction::Up);
+                }
+                // the player is still asked so that its timing and any state it keeps stay right
+                let opening = (game.history.len() as u32) < self.random_plies;
+                if opening || (self.epsilon > 0.0 && rng::rng().gen_bool(self.epsilon)) {
+                    direction = random_safe_move(&board, idx, wrapped);
+                }
+                last_moves[idx] = Some(direction);
+                let micros = elapsed.as_micros() as u64;
+                game.latency[idx].record(micros, timed_out);
+                decision_micros.push(micros);
+                moves.push(Move::new(direction, idx));
+            }
+            let applied = self.ruleset.apply_moves(board, &moves);
+            let mut spawned = applied.clone();
+            self.ruleset.spawn(&mut spawned);
+            if cfg!(debug_assertions) {
+                check_board(&spawned, wrapped);
             }
-        });
+            game.push_turn(Turn::new(moves, decision_micros, &applied, &spawned));
+            board = spawned;
+        }
+        for (idx, player) in players.iter_mut().enumerate() {
+            player.end(&info, &board, idx);
+        }
+        game
+    }
+}
+
+/// panic with every problem on the board, if there are any
+fn check_board(board: &Board, wrapped: bool) {
+    if let Err(errors) = validate(board, wrapped) {
+        let errors = errors
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>();
+        panic!(
+            "invalid board on turn {}: {}",
+            board.turn,
+            errors.join(", ")
+        );
+    }
+}
+
+/// pick a move with probability proportional to exp(score / temperature)
+fn sample_softmax(scores: &[f64; 4], temperature: f64) -> Direction {
+    let best = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if best == f64::NEG_INFINITY {
+        // every move loses, so it doesnt matter
+        return Direction::Up;
+    }
+    if temperature <= 0.0 {
+        return Direction::ALL[scores.iter().position(|s| *s == best).unwrap()];
+    }
+    // subtract the best score so that exp cant overflow
+    let weights = scores.map(|s| ((s - best) / temperature).exp());
+    let mut roll = rng::rng().gen::<f64>() * weights.iter().sum::<f64>();
+    for (direction, weight) in Direction::ALL.iter().zip(weights) {
+        if roll < weight {
+            return *direction;
+        }
+        roll -= weight;
+    }
+    Direction::ALL[scores.iter().position(|s| *s == best).unwrap()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        maps::{self, Mapped},
+        players::SafeRandomPlayer,
+    };
+
+    /// play a game with random safe moves, keeping every board along the way
+    fn play(ruleset: &(dyn Ruleset + Send + Sync), seed: u64) -> (Game, Vec<Board>) {
+        rng::seed(seed);
+        let wrapped = ruleset.name() == "wrapped";
+        let mut board = ruleset.generate_board();
+        let mut game = Game::new(seed.to_string(), ruleset.name(), board.clone());
+        let mut boards = vec![board.clone()];
+        while !ruleset.game_over(&board) && boards.len() < 300 {
+            let moves = (0..board.snakes.len())
+                .filter(|idx| board.snakes[*idx].alive)
+                .map(|idx| Move::new(random_safe_move(&board, idx, wrapped), idx))
+                .collect::<Vec<Move>>();
+            let applied = ruleset.apply_moves(board, &moves);
+            let mut spawned = applied.clone();
+            ruleset.spawn(&mut spawned);
+            let micros = vec![0; moves.len()];
+            game.push_turn(Turn::new(moves, micros, &applied, &spawned));
+            boards.push(spawned.clone());
+            board = spawned;
+        }
+        (game, boards)
+    }
+
+    fn json(board: &Board) -> serde_json::Value {
+        serde_json::to_value(board).unwrap()
+    }
+
+    #[test]
+    fn stored_games_replay_to_the_boards_they_were_played_on() {
+        let settings = ruleset::Settings {
+            shrink_every_n_turns: 5,
+            ..Default::default()
+        };
+        let mut rulesets = ["standard", "wrapped", "constrictor", "solo", "royale"]
+            .iter()
+            .map(|name| ruleset::from_name(name, 11, 11, 4, &settings).unwrap())
+            .collect::<Vec<Box<dyn Ruleset + Send + Sync>>>();
+        for map in ["hz_spiral", "arcade_maze"] {
+            let standard = ruleset::from_name("standard", 11, 11, 4, &settings).unwrap();
+            rulesets.push(Box::new(Mapped::new(
+                standard,
+                maps::from_name(map).unwrap(),
+            )));
+        }
+        for ruleset in &rulesets {
+            for seed in 0..5 {
+                let (game, played) = play(ruleset.as_ref(), seed);
+                let stored = serde_json::to_string(&game).unwrap();
+                let game: Game = serde_json::from_str(&stored).unwrap();
+                let replayed = game.boards();
+                assert_eq!(replayed.len(), played.len(), "{}", ruleset.map_name());
+                for (replayed, played) in replayed.iter().zip(&played) {
+                    assert_eq!(json(replayed), json(played), "{}", ruleset.name());
+                }
+                assert_eq!(json(game.final_board()), json(played.last().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn pushing_a_turn_forgets_the_final_board() {
+        let ruleset = ruleset::from_name("standard", 11, 11, 2, &Default::default()).unwrap();
+        let (played, _) = play(ruleset.as_ref(), 1);
+        let mut game = Game::new(String::new(), ruleset.name(), played.initial.clone());
+        assert_eq!(game.final_board().turn, 0);
+        game.push_turn(played.history[0].clone());
+        assert_eq!(game.final_board().turn, 1);
+    }
+
+    /// turns straight back into its own neck as soon as it has one
+    #[derive(Clone)]
+    struct NeckPlayer;
+
+    impl GetMove for NeckPlayer {
+        fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+            let body = &board.snakes[you_idx].body;
+            *Direction::ALL
+                .iter()
+                .find(|d| body[0] + **d == body[1])
+                .unwrap_or(&Direction::Up)
+        }
+        fn clone_dyn(&self) -> Box<dyn GetMove> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// the start, moves and food of every game, which is everything random about them
+    fn moves(games: &[Game]) -> Vec<serde_json::Value> {
         games
+            .iter()
+            .map(|g| {
+                let turns = g.history.iter().map(|t| (&t.moves, &t.spawned_food));
+                serde_json::to_value((&g.initial, turns.collect::<Vec<_>>())).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_epsilon_plays_the_same_games() {
+        let ruleset = ruleset::from_name("standard", 11, 11, 2, &Default::default()).unwrap();
+        let players = || -> Vec<Box<dyn GetMove>> {
+            vec![
+                Box::new(SafeRandomPlayer::new()),
+                Box::new(SafeRandomPlayer::new()),
+            ]
+        };
+        let games = |epsilon: Option<f64>| {
+            let mut generator = Generator::new(players(), ruleset.as_ref()).with_seed(3);
+            if let Some(epsilon) = epsilon {
+                generator = generator.with_epsilon(epsilon);
+            }
+            moves(&generator.generate_data(4))
+        };
+        assert_eq!(games(Some(0.0)), games(Some(0.0)));
+        // no epsilon doesnt even roll for it, so the games are the same as without the option
+        assert_eq!(games(Some(0.0)), games(None));
+        assert_ne!(games(Some(0.5)), games(None));
+    }
+
+    #[test]
+    fn low_temperature_takes_the_best_move() {
+        rng::seed(5);
+        let scores = [1.0, 3.0, 2.9, -4.0];
+        for _ in 0..100 {
+            assert_eq!(sample_softmax(&scores, 1e-3), Direction::ALL[1]);
+        }
+        // while a high one takes the others too
+        let picked = (0..100)
+            .map(|_| sample_softmax(&scores, 100.0))
+            .collect::<Vec<Direction>>();
+        assert!(Direction::ALL.iter().all(|d| picked.contains(d)));
+    }
+
+    #[test]
+    fn random_plies_come_before_the_players_moves() {
+        let ruleset = ruleset::from_name("standard", 11, 11, 2, &Default::default()).unwrap();
+        let players =
+            || -> Vec<Box<dyn GetMove>> { vec![Box::new(NeckPlayer), Box::new(NeckPlayer)] };
+        // both snakes turn into themselves on the first move they get to pick after their neck appears
+        let mut generator = Generator::new(players(), ruleset.as_ref()).with_seed(9);
+        for game in generator.generate_data(3) {
+            assert_eq!(game.turns(), 2);
+        }
+        let mut generator = Generator::new(players(), ruleset.as_ref())
+            .with_seed(9)
+            .with_random_plies(4);
+        for game in generator.generate_data(3) {
+            assert_eq!(game.turns(), 5);
+            assert_eq!(game.winner(), None);
+        }
+    }
+
+    #[test]
+    fn start_positions_need_a_snake_for_every_player() {
+        let ruleset = ruleset::from_name("standard", 11, 11, 2, &Default::default()).unwrap();
+        let players = vec![NeckPlayer.clone_dyn(), NeckPlayer.clone_dyn()];
+        let mut two = ruleset.generate_board();
+        two.turn = 50;
+        let mut three = Board::create_default(11, 11, 3);
+        three.turn = 70;
+        // only one snake left, so the game is already over
+        let mut over = ruleset.generate_board();
+        over.snakes[1].eliminate(crate::board::EliminationCause::OutOfHealth, 10, None);
+        over.turn = 90;
+        let mut generator = Generator::new(players, ruleset.as_ref())
+            .with_seed(1)
+            .with_start_positions(vec![three, two, over]);
+        assert_eq!(generator.start_positions.len(), 1);
+        for game in generator.generate_data(5) {
+            assert_eq!(game.initial.turn, 50);
+            assert_eq!(game.initial.snakes.len(), 2);
+        }
     }
 }
@@ -0,0 +1,457 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ureq::{Agent, AgentBuilder};
+
+use crate::{
+    board::{Board, Coordinate, Direction, Snake},
+    generator::{GameInfo, GetMove},
+};
+
+/// plays by asking a battlesnake server over http, the same way the official engine does
+#[derive(Clone)]
+pub struct HttpPlayer {
+    url: String,
+    timeout: Duration,
+    fallback: Direction,
+    agent: Agent,
+    game: Option<GameInfo>,
+}
+
+impl HttpPlayer {
+    /// url is the base url of the snake, eg `http://localhost:8000`
+    pub fn new(url: &str, timeout: Duration) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            timeout,
+            fallback: Direction::Up,
+            agent: AgentBuilder::new().timeout(timeout).build(),
+            game: None,
+        }
+    }
+
+    /// the move that is played when the snake times out or sends garbage back
+    pub fn with_fallback(mut self, fallback: Direction) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    fn request(&self, board: &Board, you_idx: usize) -> GameRequest {
+        let game = match &self.game {
+            Some(game) => game.clone(),
+            None => GameInfo {
+                game_id: String::new(),
+                ruleset_name: "standard".to_string(),
+                map_name: "standard".to_string(),
+                timeout: None,
+            },
+        };
+        GameRequest {
+            game: GameJson {
+                id: game.game_id,
+                ruleset: RulesetJson {
+                    name: game.ruleset_name,
+                    version: "v1.0.0".to_string(),
+                    settings: SettingsJson {
+                        hazard_damage_per_turn: board.hazard_damage,
+                    },
+                },
+                map: game.map_name,
+                // tell the snake about the game's limit if there is one, otherwise our own
+                timeout: game.timeout.unwrap_or(self.timeout).as_millis() as u64,
+                source: "datagen".to_string(),
+            },
+            turn: board.turn,
+            board: BoardJson {
+                height: board.height,
+                width: board.width,
+                food: board.food.iter().map(CoordinateJson::from).collect(),
+                hazards: board.hazards.iter().map(CoordinateJson::from).collect(),
+                snakes: board
+                    .snakes
+                    .iter()
+                    .filter(|s| s.alive)
+                    .map(SnakeJson::from)
+                    .collect(),
+            },
+            you: SnakeJson::from(&board.snakes[you_idx]),
+        }
+    }
+
+    fn post(&self, path: &str, request: &GameRequest) -> Result<ureq::Response, Box<ureq::Error>> {
+        self.agent
+            .post(&format!("{}/{}", self.url, path))
+            .send_json(request)
+            .map_err(Box::new)
+    }
+}
+
+impl GetMove for HttpPlayer {
+    fn start(&mut self, game: &GameInfo, board: &Board, you_idx: usize) {
+        // stop waiting at the game's limit, not our own, so a slow snake cant hold the game up
+        if let Some(limit) = game.timeout {
+            self.agent = AgentBuilder::new().timeout(limit).build();
+        }
+        self.game = Some(game.clone());
+        // the response to /start is ignored by the engine, so errors are too
+        let _ = self.post("start", &self.request(board, you_idx));
+    }
+
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        let response = self
+            .post("move", &self.request(board, you_idx))
+            .map_err(|e| e.to_string())
+            .and_then(|r| r.into_json::<MoveResponse>().map_err(|e| e.to_string()));
+        match response.map(|r| parse_direction(&r.direction)) {
+            Ok(Some(direction)) => direction,
+            _ => self.fallback,
+        }
+    }
+
+    fn end(&mut self, _game: &GameInfo, board: &Board, you_idx: usize) {
+        let _ = self.post("end", &self.request(board, you_idx));
+        self.game = None;
+    }
+
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+pub fn parse_direction(direction: &str) -> Option<Direction> {
+    match direction.to_lowercase().as_str() {
+        "up" => Some(Direction::Up),
+        "down" => Some(Direction::Down),
+        "left" => Some(Direction::Left),
+        "right" => Some(Direction::Right),
+        _ => None,
+    }
+}
+
+/// the body of a /start, /move or /end request
+#[derive(Serialize, Deserialize)]
+struct GameRequest {
+    game: GameJson,
+    turn: u32,
+    board: BoardJson,
+    you: SnakeJson,
+}
+
+// older engines and hand written requests leave out a lot of fields, so most have defaults
+#[derive(Serialize, Deserialize)]
+struct GameJson {
+    id: String,
+    ruleset: RulesetJson,
+    #[serde(default)]
+    map: String,
+    timeout: u64,
+    #[serde(default)]
+    source: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RulesetJson {
+    name: String,
+    version: String,
+    #[serde(default)]
+    settings: SettingsJson,
+}
+
+/// the only ruleset setting the boards here keep track of
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SettingsJson {
+    #[serde(default)]
+    hazard_damage_per_turn: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BoardJson {
+    height: u32,
+    width: u32,
+    food: Vec<CoordinateJson>,
+    #[serde(default)]
+    hazards: Vec<CoordinateJson>,
+    snakes: Vec<SnakeJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnakeJson {
+    id: String,
+    #[serde(default)]
+    name: String,
+    health: i8,
+    body: Vec<CoordinateJson>,
+    #[serde(default)]
+    latency: String,
+    head: CoordinateJson,
+    length: usize,
+    #[serde(default)]
+    shout: String,
+    #[serde(default)]
+    squad: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CoordinateJson {
+    x: i32,
+    y: i32,
+}
+
+/// the body of a /move response
+#[derive(Deserialize)]
+struct MoveResponse {
+    #[serde(rename = "move")]
+    direction: String,
+}
+
+/// the board and ruleset name from an official /move request, like the ones in test_jsons
+/// snakes get ids by their position in the request, and hazards do no damage unless the ruleset settings say so
+pub fn read_request(json: &str) -> serde_json::Result<(Board, String)> {
+    let request: GameRequest = serde_json::from_str(json)?;
+    let coordinates = |list: &[CoordinateJson]| {
+        list.iter()
+            .map(|c| Coordinate::new(c.x, c.y))
+            .collect::<Vec<Coordinate>>()
+    };
+    let board = Board {
+        food: coordinates(&request.board.food),
+        snakes: request
+            .board
+            .snakes
+            .iter()
+            .enumerate()
+            .map(|(idx, s)| Snake::new(coordinates(&s.body), s.health, idx))
+            .collect(),
+        hazards: coordinates(&request.board.hazards),
+        hazard_damage: request.game.ruleset.settings.hazard_damage_per_turn,
+        width: request.board.width,
+        height: request.board.height,
+        turn: request.turn,
+    };
+    Ok((board, request.game.ruleset.name))
+}
+
+impl From<&Coordinate> for CoordinateJson {
+    fn from(c: &Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+impl From<&Snake> for SnakeJson {
+    fn from(snake: &Snake) -> Self {
+        Self {
+            id: snake.id.to_string(),
+            name: format!("snake {}", snake.id),
+            health: snake.health,
+            body: snake.body.iter().map(CoordinateJson::from).collect(),
+            latency: "0".to_string(),
+            head: CoordinateJson::from(&snake.body[0]),
+            length: snake.body.len(),
+            shout: String::new(),
+            squad: String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc::{channel, Receiver},
+        thread,
+    };
+
+    use super::*;
+
+    /// a snake server that answers every request with the same body after a delay
+    /// and reports the path and json body of each request it got
+    fn stub_server(body: &'static str, delay: Duration) -> (String, Receiver<(String, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let path = line.split_whitespace().nth(1).unwrap().to_string();
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    let lower = header.to_lowercase();
+                    if let Some(value) = lower.strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                let _ = sender.send((path, String::from_utf8(content).unwrap()));
+                thread::sleep(delay);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        (url, receiver)
+    }
+
+    fn board() -> Board {
+        Board {
+            food: vec![Coordinate::new(5, 5)],
+            snakes: vec![
+                Snake::new(
+                    vec![
+                        Coordinate::new(1, 1),
+                        Coordinate::new(1, 2),
+                        Coordinate::new(1, 3),
+                    ],
+                    90,
+                    0,
+                ),
+                Snake::new(
+                    vec![
+                        Coordinate::new(9, 9),
+                        Coordinate::new(9, 8),
+                        Coordinate::new(9, 7),
+                    ],
+                    80,
+                    1,
+                ),
+            ],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 3,
+        }
+    }
+
+    fn info() -> GameInfo {
+        GameInfo {
+            game_id: "test-game".to_string(),
+            ruleset_name: "standard".to_string(),
+            map_name: "hz_rings".to_string(),
+            timeout: Some(Duration::from_millis(250)),
+        }
+    }
+
+    #[test]
+    fn parses_move_response() {
+        let (url, requests) = stub_server(r#"{"move":"left","shout":"hi"}"#, Duration::ZERO);
+        let mut player = HttpPlayer::new(&url, Duration::from_millis(500));
+        assert_eq!(player.get_move(&board(), 1), Direction::Left);
+
+        let (path, body) = requests.recv().unwrap();
+        assert_eq!(path, "/move");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["turn"], 3);
+        assert_eq!(json["you"]["id"], "1");
+        assert_eq!(json["you"]["head"]["x"], 9);
+        assert_eq!(json["board"]["snakes"].as_array().unwrap().len(), 2);
+        assert_eq!(json["board"]["food"][0]["y"], 5);
+        assert_eq!(
+            json["game"]["ruleset"]["settings"]["hazardDamagePerTurn"],
+            0
+        );
+    }
+
+    #[test]
+    fn lifecycle_calls_hit_start_and_end() {
+        let (url, requests) = stub_server(r#"{"move":"down"}"#, Duration::ZERO);
+        let mut player = HttpPlayer::new(&url, Duration::from_millis(500));
+        player.start(&info(), &board(), 0);
+        assert_eq!(player.get_move(&board(), 0), Direction::Down);
+        player.end(&info(), &board(), 0);
+
+        let paths = (0..3)
+            .map(|_| {
+                let (path, body) = requests.recv().unwrap();
+                let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+                assert_eq!(json["game"]["id"], "test-game");
+                assert_eq!(json["game"]["ruleset"]["name"], "standard");
+                assert_eq!(json["game"]["timeout"], 250);
+                assert_eq!(json["game"]["map"], "hz_rings");
+                path
+            })
+            .collect::<Vec<String>>();
+        assert_eq!(paths, vec!["/start", "/move", "/end"]);
+    }
+
+    #[test]
+    fn reads_ruleset_from_request() {
+        let json = r#"{"game":{"id":"g","ruleset":{"name":"royale","version":"v1","settings":{"hazardDamagePerTurn":14,"foodSpawnChance":15}},"timeout":500},
+            "turn":3,"board":{"height":11,"width":11,"food":[],"hazards":[{"x":0,"y":0}],
+            "snakes":[{"id":"a","health":90,"body":[{"x":1,"y":1},{"x":1,"y":2},{"x":1,"y":3}],"head":{"x":1,"y":1},"length":3}]},
+            "you":{"id":"a","health":90,"body":[{"x":1,"y":1},{"x":1,"y":2},{"x":1,"y":3}],"head":{"x":1,"y":1},"length":3}}"#;
+        let (board, ruleset) = read_request(json).unwrap();
+        assert_eq!(ruleset, "royale");
+        assert_eq!(board.hazard_damage, 14);
+        assert_eq!(board.hazards, vec![Coordinate::new(0, 0)]);
+        // requests without settings have no damage to go on
+        let json = json.replace(
+            r#","settings":{"hazardDamagePerTurn":14,"foodSpawnChance":15}"#,
+            "",
+        );
+        assert_eq!(read_request(&json).unwrap().0.hazard_damage, 0);
+    }
+
+    #[test]
+    fn falls_back_on_timeout() {
+        let (url, _requests) = stub_server(r#"{"move":"left"}"#, Duration::from_millis(300));
+        let mut player =
+            HttpPlayer::new(&url, Duration::from_millis(50)).with_fallback(Direction::Right);
+        assert_eq!(player.get_move(&board(), 0), Direction::Right);
+    }
+
+    #[test]
+    fn fallback_can_be_set_by_name() {
+        let (url, _requests) = stub_server(r#"{"move":"sideways"}"#, Duration::ZERO);
+        let name = format!("http={}#left", url);
+        let mut player = crate::players::from_name(&name).unwrap();
+        assert_eq!(player.get_move(&board(), 0), Direction::Left);
+        assert!(crate::players::from_name(&format!("http={}#north", url)).is_none());
+    }
+
+    #[test]
+    fn stops_waiting_at_the_games_limit() {
+        let (url, _requests) = stub_server(r#"{"move":"left"}"#, Duration::from_millis(300));
+        let mut player =
+            HttpPlayer::new(&url, Duration::from_millis(1000)).with_fallback(Direction::Right);
+        let game = GameInfo {
+            timeout: Some(Duration::from_millis(50)),
+            ..info()
+        };
+        player.start(&game, &board(), 0);
+        let start = std::time::Instant::now();
+        assert_eq!(player.get_move(&board(), 0), Direction::Right);
+        assert!(start.elapsed() < Duration::from_millis(300));
+    }
+
+    #[test]
+    fn falls_back_on_bad_response() {
+        let (url, _requests) = stub_server(r#"{"move":"sideways"}"#, Duration::ZERO);
+        let mut player =
+            HttpPlayer::new(&url, Duration::from_millis(500)).with_fallback(Direction::Down);
+        assert_eq!(player.get_move(&board(), 0), Direction::Down);
+    }
+
+    #[test]
+    fn falls_back_when_nothing_is_listening() {
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let mut player = HttpPlayer::new(&url, Duration::from_millis(200));
+        assert_eq!(player.get_move(&board(), 0), Direction::Up);
+    }
+}
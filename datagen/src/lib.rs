@@ -0,0 +1,23 @@
+//! the boards, rulesets and players behind datagen, for other crates in the workspace to build on
+
+pub mod analysis;
+pub mod board;
+pub mod book;
+pub mod constrictor;
+pub mod export;
+pub mod generator;
+pub mod http_player;
+pub mod maps;
+pub mod players;
+pub mod ratings;
+pub mod rng;
+pub mod royale;
+pub mod ruleset;
+pub mod sampling;
+pub mod snakedump;
+pub mod solo;
+pub mod sprt;
+pub mod standard;
+pub mod tournament;
+pub mod validate;
+pub mod wrapped;
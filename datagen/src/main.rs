@@ -1,11 +1,554 @@
-use generator::Generator;
-use standard::Standard;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, Instant},
+};
 
-mod board;
-mod generator;
-mod ruleset;
-mod standard;
+use clap::{Args, CommandFactory, ErrorKind, Parser, Subcommand};
+use datagen::{
+    analysis,
+    board::Board,
+    book::OpeningBook,
+    export::{label_game, read_json_lines, write_json_lines, LabeledPosition},
+    generator::{Game, Generator, GetMove},
+    http_player::read_request,
+    maps::{self, Mapped},
+    players,
+    ruleset::{self, Ruleset},
+    snakedump::read_snakedump,
+    sprt::{Sprt, SprtRunner},
+    tournament::{Entrant, Schedule, Tournament},
+    validate,
+};
+use indicatif::ProgressBar;
+use sampler::SampleArgs;
+
+/// self play data generation and match testing for battlesnakes
+#[derive(Parser)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// play games between a roster of players and store them
+    Generate(GenerateArgs),
+    /// label stored games from every snake's point of view
+    Label(LabelArgs),
+    /// report statistics over stored games
+    Analyze(AnalyzeArgs),
+    /// build an opening book out of stored games
+    Book(BookArgs),
+    /// check boards for anything that cant happen in a real game
+    Validate(ValidateArgs),
+    /// rate a pool of two player bots against each other
+    Tournament(TournamentArgs),
+    /// test whether a candidate is stronger than a baseline
+    Sprt(SprtArgs),
+}
+
+/// settings shared by everything that plays games
+#[derive(Args)]
+struct GameArgs {
+    /// standard, wrapped, constrictor, solo or royale
+    #[clap(long, value_parser, default_value = "standard")]
+    ruleset: String,
+    /// hz_inner_wall, hz_rings, hz_columns, hz_spiral or arcade_maze, which sets its own board size
+    #[clap(long, value_parser)]
+    map: Option<String>,
+    #[clap(long, value_parser, default_value_t = 11)]
+    width: u32,
+    #[clap(long, value_parser, default_value_t = 11)]
+    height: u32,
+    /// percent chance of food spawning each turn
+    #[clap(long, value_parser, default_value_t = 15)]
+    food_spawn_chance: u8,
+    /// food is always topped up to at least this much
+    #[clap(long, value_parser, default_value_t = 1)]
+    minimum_food: u32,
+    /// royale moves one edge of the safe area in this often, 0 never shrinks it
+    #[clap(long, value_parser, default_value_t = 25)]
+    shrink_every_n_turns: u32,
+    /// health royale takes away for every turn spent in a hazard
+    #[clap(long, value_parser, default_value_t = 14)]
+    hazard_damage: i32,
+    /// worker threads, defaults to one per core
+    #[clap(long, value_parser)]
+    threads: Option<usize>,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    #[clap(flatten)]
+    game: GameArgs,
+    /// comma separated players, repeated to fill every snake
+    /// (random, safe, greedy, floodfill, tailchaser, http=<url>, http=<url>#<fallback direction>
+    /// or book=<path>:<player>)
+    #[clap(
+        long,
+        value_parser,
+        value_delimiter = ',',
+        default_value = "floodfill,greedy"
+    )]
+    players: Vec<String>,
+    /// number of snakes in each game
+    #[clap(long, value_parser, default_value_t = 2)]
+    snakes: u8,
+    /// number of games to play
+    #[clap(long, value_parser, default_value_t = 100)]
+    games: u64,
+    /// seed for reproducible games
+    #[clap(long, value_parser)]
+    seed: Option<u64>,
+    /// milliseconds a snake gets per move, slower moves repeat the snake's last move once they finish
+    /// (http players stop waiting at the limit)
+    #[clap(long, value_parser)]
+    timeout: Option<u64>,
+    /// chance of replacing any move with a random safe one
+    #[clap(long, value_parser, default_value_t = 0.0)]
+    epsilon: f64,
+    /// sample moves from a softmax over the scores of players that score their moves
+    #[clap(long, value_parser)]
+    temperature: Option<f64>,
+    /// number of random safe moves every snake plays at the start of a game
+    #[clap(long, value_parser, default_value_t = 0)]
+    random_plies: u32,
+    /// start games from positions out of these games instead of fresh boards
+    #[clap(long, value_parser)]
+    start_from: Option<PathBuf>,
+    /// file to write the games to, one json game per line
+    #[clap(short, long, value_parser)]
+    output: Option<PathBuf>,
+    /// file to write labeled positions to, one per snake per turn
+    #[clap(long, value_parser)]
+    labels: Option<PathBuf>,
+    #[clap(flatten)]
+    sample: SampleArgs,
+}
+
+#[derive(Args)]
+struct LabelArgs {
+    /// games written by generate
+    #[clap(value_parser)]
+    input: PathBuf,
+    /// file to write the labeled positions to
+    #[clap(short, long, value_parser)]
+    output: PathBuf,
+    #[clap(flatten)]
+    sample: SampleArgs,
+}
+
+#[derive(Args)]
+struct AnalyzeArgs {
+    /// games written by generate
+    #[clap(value_parser)]
+    input: PathBuf,
+    /// print the report as json instead of text
+    #[clap(long, value_parser)]
+    json: bool,
+    /// width of the game length histogram buckets, in turns
+    #[clap(long, value_parser, default_value_t = 25)]
+    bucket_size: u64,
+}
+
+#[derive(Args)]
+struct BookArgs {
+    /// games written by generate
+    #[clap(long, value_parser)]
+    games: Vec<PathBuf>,
+    /// snakedump sqlite databases, in the format paramoptimizer reads
+    #[clap(long, value_parser)]
+    snakedump: Vec<PathBuf>,
+    /// board size of the snakedump games
+    #[clap(long, value_parser, default_value_t = 11)]
+    width: u32,
+    #[clap(long, value_parser, default_value_t = 11)]
+    height: u32,
+    /// number of turns from the start of each game to put in the book
+    #[clap(long, value_parser, default_value_t = 10)]
+    turns: u32,
+    /// file to write the book to
+    #[clap(short, long, value_parser)]
+    output: PathBuf,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    /// official move requests (like test_jsons/*.json) or .jsonl games written by generate
+    #[clap(value_parser, required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+struct TournamentArgs {
+    #[clap(flatten)]
+    game: GameArgs,
+    /// comma separated players in the pool
+    #[clap(long, value_parser, value_delimiter = ',')]
+    players: Vec<String>,
+    /// games played between each pair of players
+    #[clap(long, value_parser, default_value_t = 100)]
+    games: u64,
+    /// only play this player (by position in --players) against everyone else
+    #[clap(long, value_parser)]
+    gauntlet: Option<usize>,
+}
+
+#[derive(Args)]
+struct SprtArgs {
+    #[clap(flatten)]
+    game: GameArgs,
+    #[clap(long, value_parser)]
+    candidate: String,
+    #[clap(long, value_parser)]
+    baseline: String,
+    /// elo gain under the null hypothesis
+    #[clap(long, value_parser, default_value_t = 0.0)]
+    elo0: f64,
+    /// elo gain under the alternative hypothesis
+    #[clap(long, value_parser, default_value_t = 10.0)]
+    elo1: f64,
+    #[clap(long, value_parser, default_value_t = 0.05)]
+    alpha: f64,
+    #[clap(long, value_parser, default_value_t = 0.05)]
+    beta: f64,
+    /// games played between each llr check
+    #[clap(long, value_parser, default_value_t = 64)]
+    batch_size: u64,
+    #[clap(long, value_parser, default_value_t = 100_000)]
+    max_games: u64,
+}
+
+impl Cli {
+    /// catch settings that parse fine but cant be played
+    fn check(&self) -> Result<(), clap::Error> {
+        if let Command::Generate(args) = &self.command {
+            let room = Board::room_for_snakes(args.game.width, args.game.height);
+            if args.snakes as usize > room {
+                return Err(Cli::command().error(
+                    ErrorKind::ValueValidation,
+                    format!(
+                        "{} snakes dont fit on a {}x{} board, it has room for {}",
+                        args.snakes, args.game.width, args.game.height, room
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
 
 fn main() {
-    let x = Generator::new(vec![], Standard::new(11, 11, 2, 25));
+    let cli = Cli::parse();
+    if let Err(e) = cli.check() {
+        e.exit();
+    }
+    match cli.command {
+        Command::Generate(args) => generate(args),
+        Command::Label(args) => label(args),
+        Command::Analyze(args) => analyze(args),
+        Command::Book(args) => book(args),
+        Command::Validate(args) => validate(args),
+        Command::Tournament(args) => tournament(args),
+        Command::Sprt(args) => sprt(args),
+    }
+}
+
+/// print the message and quit
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+fn setup(game: &GameArgs, num_players: u8) -> Box<dyn Ruleset + Send + Sync> {
+    if let Some(threads) = game.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .unwrap();
+    }
+    let ruleset = ruleset::from_name(
+        &game.ruleset,
+        game.width,
+        game.height,
+        num_players,
+        &ruleset::Settings {
+            food_spawn_chance: game.food_spawn_chance,
+            minimum_food: game.minimum_food,
+            shrink_every_n_turns: game.shrink_every_n_turns,
+            hazard_damage: game.hazard_damage,
+        },
+    )
+    .unwrap_or_else(|| fail(format!("Unknown ruleset {}", game.ruleset)));
+    match &game.map {
+        Some(name) => {
+            let map =
+                maps::from_name(name).unwrap_or_else(|| fail(format!("Unknown map {}", name)));
+            Box::new(Mapped::new(ruleset, map))
+        }
+        None => ruleset,
+    }
+}
+
+fn player(name: &str) -> Box<dyn GetMove> {
+    players::from_name(name).unwrap_or_else(|| fail(format!("Unknown player {}", name)))
+}
+
+fn generate(args: GenerateArgs) {
+    let ruleset = setup(&args.game, args.snakes);
+    if args.players.is_empty() {
+        fail("Need at least one player".to_string());
+    }
+    let roster = (0..args.snakes as usize)
+        .map(|idx| args.players[idx % args.players.len()].clone())
+        .collect::<Vec<String>>();
+    // maps can change the board size, so report the size of a board the ruleset actually makes
+    let sample = ruleset.generate_board();
+    println!(
+        "Playing {} games of {} {}x{} with {}",
+        args.games,
+        ruleset.name(),
+        sample.width,
+        sample.height,
+        roster.join(", ")
+    );
+
+    let start = Instant::now();
+    let bar = ProgressBar::new(args.games);
+    let mut generator = Generator::new(roster.iter().map(|name| player(name)).collect(), ruleset)
+        .with_player_names(roster.clone())
+        .with_progress_bar(bar.clone());
+    if let Some(seed) = args.seed {
+        generator = generator.with_seed(seed);
+    }
+    if let Some(timeout) = args.timeout {
+        generator = generator.with_timeout(Duration::from_millis(timeout));
+    }
+    generator = generator
+        .with_epsilon(args.epsilon)
+        .with_random_plies(args.random_plies);
+    if let Some(temperature) = args.temperature {
+        generator = generator.with_temperature(temperature);
+    }
+    if let Some(path) = &args.start_from {
+        let games: Vec<Game> = read_json_lines(path)
+            .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+        generator = generator.with_start_positions(games.iter().flat_map(|g| g.boards()).collect());
+    }
+    let games = generator.generate_data(args.games);
+    bar.finish();
+    println!("Time taken : {:?}", Instant::now() - start);
+
+    if let Some(path) = &args.output {
+        write_json_lines(path, &games)
+            .unwrap_or_else(|e| fail(format!("unable to write {}: {}", path.display(), e)));
+        println!("Wrote {} games to {}", games.len(), path.display());
+    }
+    if let Some(path) = &args.labels {
+        write_labels(path, &games, &args.sample);
+    }
+
+    // summary
+    let turns = games.iter().map(|g| g.turns()).sum::<usize>();
+    println!("Games played: {}", games.len());
+    println!(
+        "Average length: {:.1} turns",
+        turns as f64 / games.len().max(1) as f64
+    );
+    let draws = games.iter().filter(|g| g.winner().is_none()).count();
+    for (idx, name) in roster.iter().enumerate() {
+        let wins = games.iter().filter(|g| g.winner() == Some(idx)).count();
+        let stats = games.iter().map(|g| g.latency()[idx]).collect::<Vec<_>>();
+        let moves = stats.iter().map(|s| s.moves as u64).sum::<u64>();
+        let mean = stats
+            .iter()
+            .map(|s| s.mean_micros * s.moves as u64)
+            .sum::<u64>()
+            / moves.max(1);
+        let max = stats.iter().map(|s| s.max_micros).max().unwrap_or(0);
+        let timeouts = stats.iter().map(|s| s.timeouts).sum::<u32>();
+        println!(
+            "Snake {} ({}): {} wins, {}us mean / {}us max per move, {} timeouts",
+            idx, name, wins, mean, max, timeouts
+        );
+    }
+    println!("Draws: {}", draws);
+}
+
+fn write_labels(path: &Path, games: &[Game], args: &SampleArgs) {
+    let positions = games
+        .iter()
+        .flat_map(label_game)
+        .collect::<Vec<LabeledPosition>>();
+    let labeled = positions.len();
+    let positions = args.sampler().apply(positions);
+    if positions.len() < labeled {
+        println!(
+            "Sampled {} out of {} labeled positions",
+            positions.len(),
+            labeled
+        );
+    }
+    write_json_lines(path, &positions)
+        .unwrap_or_else(|e| fail(format!("unable to write {}: {}", path.display(), e)));
+    println!(
+        "Wrote {} labeled positions to {}",
+        positions.len(),
+        path.display()
+    );
+}
+
+fn label(args: LabelArgs) {
+    let games: Vec<Game> = read_json_lines(&args.input)
+        .unwrap_or_else(|e| fail(format!("unable to read {}: {}", args.input.display(), e)));
+    write_labels(&args.output, &games, &args.sample);
+}
+
+fn analyze(args: AnalyzeArgs) {
+    let games: Vec<Game> = read_json_lines(&args.input)
+        .unwrap_or_else(|e| fail(format!("unable to read {}: {}", args.input.display(), e)));
+    let report = analysis::analyze(&games, args.bucket_size);
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        print!("{}", report);
+    }
+}
+
+fn book(args: BookArgs) {
+    let mut book = OpeningBook::new(args.turns);
+    let mut games = 0;
+    for path in &args.games {
+        let stored: Vec<Game> = read_json_lines(path)
+            .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+        stored.iter().for_each(|game| book.add_game(game));
+        games += stored.len();
+    }
+    for path in &args.snakedump {
+        let dump = read_snakedump(path, args.width, args.height)
+            .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+        if dump.skipped > 0 {
+            println!(
+                "Skipped {} games in {} that couldnt be replayed",
+                dump.skipped,
+                path.display()
+            );
+        }
+        dump.games.iter().for_each(|game| book.add_game(game));
+        games += dump.games.len();
+    }
+    book.save(&args.output)
+        .unwrap_or_else(|e| fail(format!("unable to write {}: {}", args.output.display(), e)));
+    println!(
+        "Wrote {} positions from {} games to {}",
+        book.positions.len(),
+        games,
+        args.output.display()
+    );
+}
+
+fn validate(args: ValidateArgs) {
+    let mut invalid = 0;
+    let mut checked = 0;
+    for path in &args.files {
+        // every board along with whether it is on a wrapped board
+        let boards = if path.extension().is_some_and(|e| e == "jsonl") {
+            let games: Vec<Game> = read_json_lines(path)
+                .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+            games
+                .iter()
+                .flat_map(|game| {
+                    let wrapped = game.ruleset_name() == "wrapped";
+                    game.boards().into_iter().map(move |board| (board, wrapped))
+                })
+                .collect::<Vec<(Board, bool)>>()
+        } else {
+            let json = fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+            let (board, ruleset) = read_request(&json)
+                .unwrap_or_else(|e| fail(format!("unable to read {}: {}", path.display(), e)));
+            vec![(board, ruleset == "wrapped")]
+        };
+        for (board, wrapped) in &boards {
+            checked += 1;
+            if let Err(errors) = validate::validate(board, *wrapped) {
+                invalid += 1;
+                for error in errors {
+                    println!("{} turn {}: {}", path.display(), board.turn, error);
+                }
+            }
+        }
+    }
+    println!("{} of {} boards are invalid", invalid, checked);
+    if invalid > 0 {
+        process::exit(1);
+    }
+}
+
+fn tournament(args: TournamentArgs) {
+    let ruleset = setup(&args.game, 2);
+    if args.players.len() < 2 {
+        fail("Need at least two players".to_string());
+    }
+    let schedule = match args.gauntlet {
+        Some(idx) if idx < args.players.len() => Schedule::Gauntlet(idx),
+        Some(idx) => fail(format!("No player at position {}", idx)),
+        None => Schedule::RoundRobin,
+    };
+    let entrants = args
+        .players
+        .iter()
+        .map(|name| Entrant::new(name, player(name)))
+        .collect();
+    let start = Instant::now();
+    let results = Tournament::new(entrants, ruleset, schedule, args.games).run();
+    println!();
+    println!("{}", results);
+    println!("Time taken : {:?}", Instant::now() - start);
+}
+
+fn sprt(args: SprtArgs) {
+    let ruleset = setup(&args.game, 2);
+    let test = Sprt::new(args.elo0, args.elo1, args.alpha, args.beta);
+    println!(
+        "SPRT {} vs {}, elo0 {} elo1 {} alpha {} beta {}",
+        args.candidate, args.baseline, args.elo0, args.elo1, args.alpha, args.beta
+    );
+    let start = Instant::now();
+    let outcome = SprtRunner::new(
+        player(&args.candidate),
+        player(&args.baseline),
+        ruleset,
+        test,
+    )
+    .with_batch_size(args.batch_size)
+    .with_max_games(args.max_games)
+    .run();
+    println!(
+        "Finished after {} games with llr {:.2}: {:?}",
+        outcome.wins + outcome.draws + outcome.losses,
+        outcome.llr,
+        outcome.result
+    );
+    println!("Time taken : {:?}", Instant::now() - start);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(args: &[&str]) -> Result<(), clap::Error> {
+        Cli::try_parse_from(["datagen", "generate"].iter().chain(args))?.check()
+    }
+
+    #[test]
+    fn too_many_snakes_is_a_usage_error() {
+        let error = check(&["--snakes", "40", "--width", "7", "--height", "7"]).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ValueValidation);
+        assert!(check(&["--snakes", "61", "--width", "11", "--height", "11"]).is_err());
+        assert!(check(&["--snakes", "60", "--width", "11", "--height", "11"]).is_ok());
+        assert!(check(&["--snakes", "10", "--width", "19", "--height", "19"]).is_ok());
+    }
 }
@@ -0,0 +1,392 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::{
+    board::{Board, Coordinate, Direction, Snake},
+    rng::rng,
+    ruleset::{Move, Ruleset},
+};
+
+/// damage taken per turn in a hazard on the maps that dont set their own
+const HAZARD_DAMAGE: i32 = 14;
+
+/// a board layout that is laid over a ruleset, like the official game maps
+pub trait Map: Send + Sync {
+    fn name(&self) -> String;
+    /// place the snakes, food and hazards on a board the ruleset just generated
+    fn setup_board(&self, board: &mut Board);
+    /// change the board at the end of every turn, after the moves were applied
+    fn update_board(&self, _board: &mut Board) {}
+    /// whether the ruleset should keep spawning food the way it normally does
+    fn spawns_food(&self) -> bool {
+        true
+    }
+}
+
+/// look up one of the maps by its official name
+pub fn from_name(name: &str) -> Option<Box<dyn Map>> {
+    match name {
+        "standard" => Some(Box::new(StandardMap)),
+        "hz_inner_wall" => Some(Box::new(InnerWall)),
+        "hz_rings" => Some(Box::new(Rings)),
+        "hz_columns" => Some(Box::new(Columns)),
+        "hz_spiral" => Some(Box::new(Spiral)),
+        "arcade_maze" => Some(Box::new(ArcadeMaze)),
+        _ => None,
+    }
+}
+
+/// a ruleset played on a map
+pub struct Mapped<R>
+where
+    R: Ruleset,
+{
+    ruleset: R,
+    map: Box<dyn Map>,
+}
+
+impl<R> Mapped<R>
+where
+    R: Ruleset,
+{
+    pub fn new(ruleset: R, map: Box<dyn Map>) -> Self {
+        Self { ruleset, map }
+    }
+}
+
+impl<R> Ruleset for Mapped<R>
+where
+    R: Ruleset,
+{
+    // the map only changes the random part of a turn, so the game replays under the plain ruleset
+    fn name(&self) -> String {
+        self.ruleset.name()
+    }
+    fn map_name(&self) -> String {
+        self.map.name()
+    }
+    fn generate_board(&self) -> Board {
+        let mut board = self.ruleset.generate_board();
+        self.map.setup_board(&mut board);
+        board
+    }
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board {
+        self.ruleset.apply_moves(board, moves)
+    }
+    fn spawn(&self, board: &mut Board) {
+        if self.map.spawns_food() {
+            self.ruleset.spawn(board);
+        } else {
+            self.ruleset.spawn_hazards(board);
+        }
+        self.map.update_board(board);
+    }
+    fn spawn_hazards(&self, board: &mut Board) {
+        self.ruleset.spawn_hazards(board);
+    }
+    fn game_over(&self, board: &Board) -> bool {
+        self.ruleset.game_over(board)
+    }
+}
+
+/// add hazards to the board, on top of whatever is there like the official maps do
+fn add_hazards(board: &mut Board, hazards: impl Iterator<Item = Coordinate>) {
+    board.hazards.extend(hazards);
+    if board.hazard_damage == 0 {
+        board.hazard_damage = HAZARD_DAMAGE;
+    }
+}
+
+/// the squares of a one wide rectangle `offset` squares in from the edge of the board
+fn ring(board: &Board, offset: i32) -> Vec<Coordinate> {
+    let (max_x, max_y) = (
+        board.width as i32 - 1 - offset,
+        board.height as i32 - 1 - offset,
+    );
+    (offset..=max_x)
+        .flat_map(|x| (offset..=max_y).map(move |y| Coordinate::new(x, y)))
+        .filter(|c| c.x == offset || c.x == max_x || c.y == offset || c.y == max_y)
+        .collect()
+}
+
+/// the board as the ruleset made it
+pub struct StandardMap;
+
+impl Map for StandardMap {
+    fn name(&self) -> String {
+        "standard".to_string()
+    }
+    fn setup_board(&self, _board: &mut Board) {}
+}
+
+/// a wall of hazards two squares in from the edge
+pub struct InnerWall;
+
+impl Map for InnerWall {
+    fn name(&self) -> String {
+        "hz_inner_wall".to_string()
+    }
+    fn setup_board(&self, board: &mut Board) {
+        let wall = ring(board, 2);
+        add_hazards(board, wall.into_iter());
+    }
+}
+
+/// rings of hazards every other square out from the middle, starting two squares out
+pub struct Rings;
+
+impl Map for Rings {
+    fn name(&self) -> String {
+        "hz_rings".to_string()
+    }
+    fn setup_board(&self, board: &mut Board) {
+        let smallest_side = board.width.min(board.height) as i32;
+        let middle = (smallest_side - 1) / 2;
+        let rings = (2..smallest_side / 2)
+            .step_by(2)
+            .flat_map(|distance| ring(board, middle - distance))
+            .collect::<Vec<Coordinate>>();
+        add_hazards(board, rings.into_iter());
+    }
+}
+
+/// single hazard squares on every odd x and y
+pub struct Columns;
+
+impl Map for Columns {
+    fn name(&self) -> String {
+        "hz_columns".to_string()
+    }
+    fn setup_board(&self, board: &mut Board) {
+        let (width, height) = (board.width as i32, board.height as i32);
+        let columns = (0..width)
+            .flat_map(|x| (0..height).map(move |y| Coordinate::new(x, y)))
+            .filter(|c| c.x % 2 == 1 && c.y % 2 == 1)
+            .collect::<Vec<Coordinate>>();
+        add_hazards(board, columns.into_iter());
+    }
+}
+
+/// hazards that grow in a clockwise spiral from a random square near the middle
+pub struct Spiral;
+
+/// the spiral grows by one square this often, starting on turn 3
+const SPIRAL_EVERY_N_TURNS: u32 = 3;
+
+/// the spiral starts somewhere between this fraction of the board and one minus it
+const SPIRAL_SPAWN_AREA: f64 = 0.3;
+
+/// the square `steps` squares along a clockwise spiral out of center
+fn spiral_square(center: Coordinate, steps: u32) -> Coordinate {
+    let turns = [
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::Up,
+    ];
+    let mut square = center;
+    let mut remaining = steps;
+    // runs go 1, 1, 2, 2, 3, 3, ... squares before turning
+    let mut run = 1;
+    let mut leg = 0;
+    while remaining > 0 {
+        let length = run.min(remaining);
+        for _ in 0..length {
+            square = square + turns[leg % 4];
+        }
+        remaining -= length;
+        leg += 1;
+        if leg % 2 == 0 {
+            run += 1;
+        }
+    }
+    square
+}
+
+impl Map for Spiral {
+    fn name(&self) -> String {
+        "hz_spiral".to_string()
+    }
+    fn setup_board(&self, board: &mut Board) {
+        board.hazards = vec![];
+        if board.hazard_damage == 0 {
+            board.hazard_damage = HAZARD_DAMAGE;
+        }
+    }
+    fn update_board(&self, board: &mut Board) {
+        if board.turn == 0 || !board.turn.is_multiple_of(SPIRAL_EVERY_N_TURNS) {
+            return;
+        }
+        // the center is always the first hazard, which is where the spiral carries on from
+        let center = match board.hazards.first() {
+            Some(center) => *center,
+            None => {
+                let between = |side: u32| {
+                    let side = side as f64;
+                    (side * SPIRAL_SPAWN_AREA) as i32..=(side * (1.0 - SPIRAL_SPAWN_AREA)) as i32
+                };
+                let mut rng = rng();
+                let center = Coordinate::new(
+                    rng.gen_range(between(board.width)),
+                    rng.gen_range(between(board.height)),
+                );
+                board.hazards.push(center);
+                return;
+            }
+        };
+        // squares off the board still count, so the spiral keeps its shape along the edges
+        let square = spiral_square(center, board.turn / SPIRAL_EVERY_N_TURNS - 1);
+        if board.in_bounds(&square) {
+            board.hazards.push(square);
+        }
+    }
+}
+
+/// the 19x21 arcade maze, top row first
+/// `#` is a wall, `S` a starting square and `F` a square food spawns on, laid out like the official arcade_maze
+const ARCADE_MAZE: [&str; 21] = [
+    "###################",
+    "#...S....#....S...#",
+    "#.##.###.#.###.##.#",
+    "#...F....F....F...#",
+    "#.##.#.#####.#.##.#",
+    "#....#...#...#....#",
+    "####.###.#.###.####",
+    "####.#.......#.####",
+    "####.#.##.##.#.####",
+    "#S.F...#.F.#...F.S#",
+    "####.#.#####.#.####",
+    "####.#.......#.####",
+    "####.#.#####.#.####",
+    "#...F....#....F...#",
+    "#.##.###.#.###.##.#",
+    "#..#.....F.....#..#",
+    "##.#.#.#####.#.#.##",
+    "#....#...#...#....#",
+    "#.######.#.######.#",
+    "#F..S....F....S..F#",
+    "###################",
+];
+
+/// a maze with deadly walls, where food only appears on fixed squares
+pub struct ArcadeMaze;
+
+impl ArcadeMaze {
+    /// every square in the layout marked with the given character
+    fn squares(mark: char) -> Vec<Coordinate> {
+        let height = ARCADE_MAZE.len() as i32;
+        ARCADE_MAZE
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(move |(_, c)| *c == mark)
+                    .map(move |(x, _)| Coordinate::new(x as i32, height - 1 - row as i32))
+            })
+            .collect()
+    }
+}
+
+impl Map for ArcadeMaze {
+    fn name(&self) -> String {
+        "arcade_maze".to_string()
+    }
+    fn setup_board(&self, board: &mut Board) {
+        board.width = ARCADE_MAZE[0].len() as u32;
+        board.height = ARCADE_MAZE.len() as u32;
+        board.hazards = Self::squares('#');
+        board.hazard_damage = 100;
+        board.food = Self::squares('F');
+        let mut starts = Self::squares('S');
+        starts.shuffle(&mut rng());
+        // any snakes past the fixed starts go on random open squares
+        let mut open = Self::squares('.');
+        open.shuffle(&mut rng());
+        starts.extend(open);
+        board.snakes = (0..board.snakes.len())
+            .map(|idx| Snake::new(vec![starts[idx]; 3], 100, idx))
+            .collect();
+    }
+    fn update_board(&self, board: &mut Board) {
+        // one more food whenever the board is empty, and every ten turns otherwise
+        if !board.food.is_empty() && !board.turn.is_multiple_of(10) {
+            return;
+        }
+        let free = Self::squares('F')
+            .into_iter()
+            .filter(|c| {
+                !board.food.contains(c)
+                    && !board.snakes.iter().any(|s| s.alive && s.body.contains(c))
+            })
+            .collect::<Vec<Coordinate>>();
+        if let Some(food) = free.choose(&mut rng()) {
+            board.food.push(*food);
+        }
+    }
+    fn spawns_food(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{royale::Royale, standard::Standard};
+
+    #[test]
+    fn rings_go_out_from_the_middle() {
+        let board = Mapped::new(Standard::new(11, 11, 2, 15), Box::new(Rings)).generate_board();
+        for square in &board.hazards {
+            let distance = (square.x - 5).abs().max((square.y - 5).abs());
+            assert!(distance == 2 || distance == 4, "{:?}", square);
+        }
+        assert_eq!(board.hazards.len(), 16 + 32);
+    }
+
+    #[test]
+    fn spiral_starts_on_turn_three() {
+        let mut board = Mapped::new(Standard::new(11, 11, 2, 0), Box::new(Spiral)).generate_board();
+        let mut grown = vec![];
+        for turn in 1..=9 {
+            board.turn = turn;
+            Spiral.update_board(&mut board);
+            grown.push(board.hazards.len());
+        }
+        assert_eq!(grown, vec![0, 0, 1, 1, 1, 2, 2, 2, 3]);
+        let center = board.hazards[0];
+        assert!((3..=7).contains(&center.x) && (3..=7).contains(&center.y));
+        assert_eq!(board.hazards[1], center + Direction::Right);
+    }
+
+    #[test]
+    fn arcade_maze_starts_everyone_in_the_open() {
+        let board =
+            Mapped::new(Standard::new(11, 11, 4, 15), Box::new(ArcadeMaze)).generate_board();
+        assert_eq!((board.width, board.height), (19, 21));
+        assert_eq!(board.food.len(), 12);
+        for snake in &board.snakes {
+            assert!(!board.hazards.contains(&snake.body[0]));
+            assert!(!board.food.contains(&snake.body[0]));
+        }
+    }
+
+    #[test]
+    fn royale_keeps_shrinking_on_arcade_maze() {
+        crate::rng::seed(4);
+        let royale = Royale::new(11, 11, 2, 15).with_shrink_every_n_turns(5);
+        let ruleset = Mapped::new(royale, Box::new(ArcadeMaze));
+        let mut board = ruleset.generate_board();
+        let walls = board.hazards.clone();
+        let mut grown = vec![];
+        for turn in 1..=20 {
+            let before = board.hazards.len();
+            board.turn = turn;
+            ruleset.spawn(&mut board);
+            grown.push(board.hazards.len() > before);
+            assert!(walls.iter().all(|wall| board.hazards.contains(wall)));
+        }
+        let every_fifth = (1..=20).map(|turn| turn % 5 == 0).collect::<Vec<bool>>();
+        assert_eq!(grown, every_fifth);
+    }
+}
@@ -0,0 +1,442 @@
+use std::{collections::VecDeque, path::Path, sync::Arc, time::Duration};
+
+use rand::seq::SliceRandom;
+
+use crate::{
+    board::{Board, Coordinate, Direction},
+    book::{BookPlayer, OpeningBook},
+    generator::{GameInfo, GetMove},
+    http_player::{parse_direction, HttpPlayer},
+    rng::rng,
+};
+
+/// look up one of the built in players by name
+/// `http=<url>` plays through a snake server running at url, waiting as long as the game's timeout
+/// `http=<url>#<direction>` does the same, moving in direction when the server doesnt answer in time
+/// `book=<path>:<player>` plays out of the opening book at path, then as player
+pub fn from_name(name: &str) -> Option<Box<dyn GetMove>> {
+    if let Some(url) = name.strip_prefix("http=") {
+        let player = match url.rsplit_once('#') {
+            Some((url, fallback)) => HttpPlayer::new(url, Duration::from_millis(500))
+                .with_fallback(parse_direction(fallback)?),
+            None => HttpPlayer::new(url, Duration::from_millis(500)),
+        };
+        return Some(Box::new(player));
+    }
+    if let Some(rest) = name.strip_prefix("book=") {
+        let (path, inner) = rest.split_once(':')?;
+        let book = OpeningBook::load(Path::new(path)).ok()?;
+        return Some(Box::new(BookPlayer::new(Arc::new(book), from_name(inner)?)));
+    }
+    match name {
+        "random" => Some(Box::new(RandomPlayer::new())),
+        "safe" => Some(Box::new(SafeRandomPlayer::new())),
+        "greedy" => Some(Box::new(GreedyPlayer::new())),
+        "floodfill" => Some(Box::new(FloodFillPlayer::new())),
+        "tailchaser" => Some(Box::new(TailChaserPlayer::new())),
+        _ => None,
+    }
+}
+
+/// picks one of the four directions uniformly at random, even if it kills itself
+#[derive(Clone, Default)]
+pub struct RandomPlayer;
+
+impl RandomPlayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl GetMove for RandomPlayer {
+    fn get_move(&mut self, _board: &Board, _you_idx: usize) -> Direction {
+        *Direction::ALL.choose(&mut rng()).unwrap()
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+/// picks a random move out of the ones that dont immediately kill it
+#[derive(Clone, Default)]
+pub struct SafeRandomPlayer {
+    wrapped: bool,
+}
+
+impl SafeRandomPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GetMove for SafeRandomPlayer {
+    fn start(&mut self, game: &GameInfo, _board: &Board, _you_idx: usize) {
+        self.wrapped = game.ruleset_name == "wrapped";
+    }
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        random_safe_move(board, you_idx, self.wrapped)
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+/// walks the shortest path to the nearest reachable food
+#[derive(Clone, Default)]
+pub struct GreedyPlayer {
+    wrapped: bool,
+}
+
+impl GreedyPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GetMove for GreedyPlayer {
+    fn start(&mut self, game: &GameInfo, _board: &Board, _you_idx: usize) {
+        self.wrapped = game.ruleset_name == "wrapped";
+    }
+    fn scores(&mut self, board: &Board, you_idx: usize) -> Option<[f64; 4]> {
+        let head = board.snakes[you_idx].body[0];
+        let blocked = obstacles(board);
+        let moves = safe_moves(board, you_idx, self.wrapped);
+        // closer food is better, and staying alive without food beats dying
+        let no_food = -((board.width * board.height) as f64);
+        Some(Direction::ALL.map(|dir| {
+            if !moves.contains(&dir) {
+                return f64::NEG_INFINITY;
+            }
+            let next = step(board, head, dir, self.wrapped);
+            distance_to(board, &blocked, next, self.wrapped, |c| {
+                board.food.contains(c)
+            })
+            .map_or(no_food, |dist| -(dist as f64))
+        }))
+    }
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        let head = board.snakes[you_idx].body[0];
+        let blocked = obstacles(board);
+        let moves = safe_moves(board, you_idx, self.wrapped);
+        // distance to the closest food after making each move
+        let best = moves
+            .iter()
+            .filter_map(|dir| {
+                let next = step(board, head, *dir, self.wrapped);
+                distance_to(board, &blocked, next, self.wrapped, |c| {
+                    board.food.contains(c)
+                })
+                .map(|dist| (*dir, dist))
+            })
+            .min_by_key(|(_, dist)| *dist);
+        match best {
+            Some((dir, _)) => dir,
+            // no food is reachable, so just stay alive
+            None => *moves.choose(&mut rng()).unwrap_or(&Direction::Up),
+        }
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+/// picks the move that leaves it with the most reachable space
+#[derive(Clone, Default)]
+pub struct FloodFillPlayer {
+    wrapped: bool,
+}
+
+impl FloodFillPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GetMove for FloodFillPlayer {
+    fn start(&mut self, game: &GameInfo, _board: &Board, _you_idx: usize) {
+        self.wrapped = game.ruleset_name == "wrapped";
+    }
+    fn scores(&mut self, board: &Board, you_idx: usize) -> Option<[f64; 4]> {
+        let head = board.snakes[you_idx].body[0];
+        let blocked = obstacles(board);
+        let moves = safe_moves(board, you_idx, self.wrapped);
+        Some(Direction::ALL.map(|dir| {
+            if moves.contains(&dir) {
+                flood_fill(
+                    board,
+                    &blocked,
+                    step(board, head, dir, self.wrapped),
+                    self.wrapped,
+                ) as f64
+            } else {
+                f64::NEG_INFINITY
+            }
+        }))
+    }
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        let head = board.snakes[you_idx].body[0];
+        let blocked = obstacles(board);
+        let mut moves = safe_moves(board, you_idx, self.wrapped);
+        // shuffle first so that ties are broken randomly
+        moves.shuffle(&mut rng());
+        moves
+            .into_iter()
+            .max_by_key(|dir| {
+                flood_fill(
+                    board,
+                    &blocked,
+                    step(board, head, *dir, self.wrapped),
+                    self.wrapped,
+                )
+            })
+            .unwrap_or(Direction::Up)
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+/// follows its own tail around, which keeps it alive in open space for a long time
+#[derive(Clone, Default)]
+pub struct TailChaserPlayer {
+    wrapped: bool,
+}
+
+impl TailChaserPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl GetMove for TailChaserPlayer {
+    fn start(&mut self, game: &GameInfo, _board: &Board, _you_idx: usize) {
+        self.wrapped = game.ruleset_name == "wrapped";
+    }
+    fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+        let snake = &board.snakes[you_idx];
+        let head = snake.body[0];
+        let tail = *snake.body.last().unwrap();
+        let blocked = obstacles(board);
+        let moves = safe_moves(board, you_idx, self.wrapped);
+        let best = moves
+            .iter()
+            .filter_map(|dir| {
+                let next = step(board, head, *dir, self.wrapped);
+                distance_to(board, &blocked, next, self.wrapped, |c| *c == tail)
+                    .map(|dist| (*dir, dist))
+            })
+            .min_by_key(|(_, dist)| *dist);
+        match best {
+            Some((dir, _)) => dir,
+            // tail is cut off, fall back to whatever has the most room
+            None => moves
+                .into_iter()
+                .max_by_key(|dir| {
+                    flood_fill(
+                        board,
+                        &blocked,
+                        step(board, head, *dir, self.wrapped),
+                        self.wrapped,
+                    )
+                })
+                .unwrap_or(Direction::Up),
+        }
+    }
+    fn clone_dyn(&self) -> Box<dyn GetMove> {
+        Box::new(self.clone())
+    }
+}
+
+/// occupancy grid of every square that will still be filled by a snake next turn
+/// tails move out of the way unless the snake just ate (stacked tail)
+fn obstacles(board: &Board) -> Vec<bool> {
+    let mut grid = vec![false; (board.width * board.height) as usize];
+    for snake in board.snakes.iter().filter(|s| s.alive) {
+        let len = snake.body.len();
+        let stacked = len > 1 && snake.body[len - 1] == snake.body[len - 2];
+        let end = if stacked { len } else { len - 1 };
+        for piece in &snake.body[..end] {
+            if board.in_bounds(piece) {
+                grid[index(board, piece)] = true;
+            }
+        }
+    }
+    // hazards that kill even a snake at full health are as good as walls
+    if board.hazard_damage >= 100 {
+        for hazard in board.hazards.iter().filter(|h| board.in_bounds(h)) {
+            grid[index(board, hazard)] = true;
+        }
+    }
+    grid
+}
+
+/// a random move out of the ones that dont immediately kill the snake, up if there are none
+pub fn random_safe_move(board: &Board, you_idx: usize, wrapped: bool) -> Direction {
+    *safe_moves(board, you_idx, wrapped)
+        .choose(&mut rng())
+        .unwrap_or(&Direction::Up)
+}
+
+/// moves that dont run into a wall or a body, preferring ones that also avoid losing head to heads
+fn safe_moves(board: &Board, you_idx: usize, wrapped: bool) -> Vec<Direction> {
+    let blocked = obstacles(board);
+    let me = &board.snakes[you_idx];
+    let head = me.body[0];
+    let safe = Direction::ALL
+        .iter()
+        .filter(|dir| {
+            let next = step(board, head, **dir, wrapped);
+            board.in_bounds(&next) && !blocked[index(board, &next)]
+        })
+        .cloned()
+        .collect::<Vec<Direction>>();
+    // squares that a snake at least as long as me could also move into
+    let contested = |c: &Coordinate| {
+        board.snakes.iter().enumerate().any(|(idx, other)| {
+            idx != you_idx
+                && other.alive
+                && other.body.len() >= me.body.len()
+                && Direction::ALL
+                    .iter()
+                    .any(|dir| step(board, other.body[0], *dir, wrapped) == *c)
+        })
+    };
+    let uncontested = safe
+        .iter()
+        .filter(|dir| !contested(&step(board, head, **dir, wrapped)))
+        .cloned()
+        .collect::<Vec<Direction>>();
+    if uncontested.is_empty() {
+        safe
+    } else {
+        uncontested
+    }
+}
+
+/// number of free squares reachable from start
+fn flood_fill(board: &Board, blocked: &[bool], start: Coordinate, wrapped: bool) -> usize {
+    if !board.in_bounds(&start) || blocked[index(board, &start)] {
+        return 0;
+    }
+    let mut seen = blocked.to_vec();
+    seen[index(board, &start)] = true;
+    let mut queue = VecDeque::from([start]);
+    let mut count = 0;
+    while let Some(current) = queue.pop_front() {
+        count += 1;
+        for dir in Direction::ALL {
+            let next = step(board, current, dir, wrapped);
+            if board.in_bounds(&next) && !seen[index(board, &next)] {
+                seen[index(board, &next)] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    count
+}
+
+/// bfs distance from start to the nearest square matching the goal
+/// goal squares are allowed to be blocked (so that a tail can be targeted)
+fn distance_to<F>(
+    board: &Board,
+    blocked: &[bool],
+    start: Coordinate,
+    wrapped: bool,
+    goal: F,
+) -> Option<u32>
+where
+    F: Fn(&Coordinate) -> bool,
+{
+    if !board.in_bounds(&start) {
+        return None;
+    }
+    if goal(&start) {
+        return Some(0);
+    }
+    if blocked[index(board, &start)] {
+        return None;
+    }
+    let mut seen = vec![false; blocked.len()];
+    seen[index(board, &start)] = true;
+    let mut queue = VecDeque::from([(start, 0)]);
+    while let Some((current, dist)) = queue.pop_front() {
+        for dir in Direction::ALL {
+            let next = step(board, current, dir, wrapped);
+            if !board.in_bounds(&next) || seen[index(board, &next)] {
+                continue;
+            }
+            if goal(&next) {
+                return Some(dist + 1);
+            }
+            seen[index(board, &next)] = true;
+            if !blocked[index(board, &next)] {
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    None
+}
+
+/// the square a move from c lands on, coming back in on the other side on a wrapped board
+fn step(board: &Board, c: Coordinate, dir: Direction, wrapped: bool) -> Coordinate {
+    let next = c + dir;
+    if !wrapped {
+        return next;
+    }
+    Coordinate::new(
+        next.x.rem_euclid(board.width as i32),
+        next.y.rem_euclid(board.height as i32),
+    )
+}
+
+fn index(board: &Board, c: &Coordinate) -> usize {
+    (c.x + c.y * board.width as i32) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Snake;
+
+    #[test]
+    fn wrapped_players_leave_across_the_edge() {
+        let snake = |squares: [(i32, i32); 3], id| {
+            let body = squares.iter().map(|(x, y)| Coordinate::new(*x, *y));
+            Snake::new(body.collect(), 100, id)
+        };
+        // boxed in on every side but the left edge of the board
+        let board = Board {
+            food: vec![],
+            snakes: vec![
+                snake([(0, 5), (1, 5), (2, 5)], 0),
+                snake([(0, 4), (0, 3), (0, 2)], 1),
+                snake([(0, 6), (0, 7), (0, 8)], 2),
+            ],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 0,
+        };
+        assert!(safe_moves(&board, 0, false).is_empty());
+        assert_eq!(safe_moves(&board, 0, true), vec![Direction::Left]);
+
+        let game = GameInfo {
+            game_id: String::new(),
+            ruleset_name: "wrapped".to_string(),
+            map_name: "standard".to_string(),
+            timeout: None,
+        };
+        let mut player = FloodFillPlayer::new();
+        player.start(&game, &board, 0);
+        assert_eq!(player.get_move(&board, 0), Direction::Left);
+        let scores = player.scores(&board, 0).unwrap();
+        assert!(
+            scores[Direction::ALL
+                .iter()
+                .position(|d| *d == Direction::Left)
+                .unwrap()]
+                > 100.0
+        );
+    }
+}
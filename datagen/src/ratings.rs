@@ -0,0 +1,170 @@
+use std::f64::consts::LN_10;
+
+/// z score for a 95% confidence interval
+const Z_95: f64 = 1.959964;
+
+/// expected score of a player rated `elo` points above their opponent
+pub fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// elo difference implied by a score between 0 and 1
+pub fn score_to_elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/// elo difference from a win/draw/loss record and the half width of its 95% confidence interval
+pub fn elo_difference(wins: u64, draws: u64, losses: u64) -> (f64, f64) {
+    let n = (wins + draws + losses) as f64;
+    if n == 0.0 {
+        return (0.0, f64::INFINITY);
+    }
+    // a perfect score is kept half a game short of perfect so the elo stays finite
+    let clamp = |score: f64| score.clamp(0.5 / n, 1.0 - 0.5 / n);
+    let score = clamp((wins as f64 + draws as f64 / 2.0) / n);
+    // variance of a single game's result around the mean score
+    let mut variance = (wins as f64 * (1.0 - score).powi(2)
+        + draws as f64 * (0.5 - score).powi(2)
+        + losses as f64 * score.powi(2))
+        / n;
+    // every game going the same way says nothing about the spread, so assume no draws
+    let outcomes = [wins, draws, losses]
+        .iter()
+        .filter(|count| **count > 0)
+        .count();
+    if outcomes == 1 {
+        variance = variance.max(score * (1.0 - score));
+    }
+    let deviation = (variance / n).sqrt();
+    let lower = score_to_elo(clamp(score - Z_95 * deviation));
+    let upper = score_to_elo(clamp(score + Z_95 * deviation));
+    (score_to_elo(score), (upper - lower) / 2.0)
+}
+
+/// a fitted rating with the half width of its 95% confidence interval
+#[derive(Clone, Copy, Debug)]
+pub struct Rating {
+    pub elo: f64,
+    pub margin: f64,
+}
+
+/// fit elo ratings (centred on 0) to the games between every pair of players
+/// `games[i][j]` is the number of games i played against j and `points[i][j]` the points i scored in them
+/// every pair that played gets one extra virtual draw so that perfect scores dont run off to infinity
+pub fn fit_elo(games: &[Vec<f64>], points: &[Vec<f64>]) -> Vec<Rating> {
+    let n = games.len();
+    let prior = |i: usize, j: usize| if games[i][j] > 0.0 { 1.0 } else { 0.0 };
+    let games = (0..n)
+        .map(|i| (0..n).map(|j| games[i][j] + prior(i, j)).collect())
+        .collect::<Vec<Vec<f64>>>();
+    let points = (0..n)
+        .map(|i| (0..n).map(|j| points[i][j] + prior(i, j) / 2.0).collect())
+        .collect::<Vec<Vec<f64>>>();
+
+    // fisher information of player i's rating, in elo units
+    let information = |ratings: &[f64], i: usize| {
+        let scale = LN_10 / 400.0;
+        (0..n)
+            .map(|j| {
+                let p = expected_score(ratings[i] - ratings[j]);
+                games[i][j] * p * (1.0 - p) * scale * scale
+            })
+            .sum::<f64>()
+    };
+
+    let mut ratings = vec![0.0; n];
+    for _ in 0..1000 {
+        let mut largest_step: f64 = 0.0;
+        for i in 0..n {
+            let info = information(&ratings, i);
+            if info == 0.0 {
+                continue;
+            }
+            let expected = (0..n)
+                .map(|j| games[i][j] * expected_score(ratings[i] - ratings[j]))
+                .sum::<f64>();
+            let actual = points[i].iter().sum::<f64>();
+            // newton step on the log likelihood
+            let step = ((actual - expected) * LN_10 / 400.0 / info).clamp(-100.0, 100.0);
+            ratings[i] += step;
+            largest_step = largest_step.max(step.abs());
+        }
+        let mean = ratings.iter().sum::<f64>() / n as f64;
+        ratings.iter_mut().for_each(|r| *r -= mean);
+        if largest_step < 1e-6 {
+            break;
+        }
+    }
+
+    (0..n)
+        .map(|i| {
+            let info = information(&ratings, i);
+            Rating {
+                elo: ratings[i],
+                margin: Z_95 / info.sqrt(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn even_scores_are_level() {
+        let (elo, margin) = elo_difference(5, 0, 5);
+        assert!(elo.abs() < 1e-9);
+        assert!(margin > 0.0 && margin.is_finite());
+        // draws say the players are level too, just with less spread
+        let (elo, drawn_margin) = elo_difference(0, 10, 0);
+        assert!(elo.abs() < 1e-9);
+        assert!(drawn_margin.is_finite());
+        assert_eq!(elo_difference(0, 0, 0), (0.0, f64::INFINITY));
+    }
+
+    #[test]
+    fn one_sided_scores_stay_finite() {
+        let (best, best_margin) = elo_difference(10, 0, 0);
+        let (worst, worst_margin) = elo_difference(0, 0, 10);
+        // half a game short of a perfect score
+        assert!((best - score_to_elo(0.95)).abs() < 1e-9);
+        assert!((best + worst).abs() < 1e-9);
+        assert!(best_margin.is_finite() && best_margin > 0.0);
+        assert!((best_margin - worst_margin).abs() < 1e-9);
+    }
+
+    #[test]
+    fn two_players_fit_their_score() {
+        let games = vec![vec![0.0, 100.0], vec![100.0, 0.0]];
+        let points = vec![vec![0.0, 75.0], vec![25.0, 0.0]];
+        let ratings = fit_elo(&games, &points);
+        // the virtual draw counts as one more game and half a point each
+        let expected = score_to_elo(75.5 / 101.0);
+        assert!((ratings[0].elo - ratings[1].elo - expected).abs() < 1e-3);
+        assert!((ratings[0].elo + ratings[1].elo).abs() < 1e-6);
+        assert!(ratings[0].margin.is_finite());
+    }
+
+    #[test]
+    fn a_player_that_lost_everything_gets_a_finite_rating() {
+        // a and b split their games, c lost every game to both
+        let games = vec![
+            vec![0.0, 20.0, 10.0],
+            vec![20.0, 0.0, 10.0],
+            vec![10.0, 10.0, 0.0],
+        ];
+        let points = vec![
+            vec![0.0, 10.0, 10.0],
+            vec![10.0, 0.0, 10.0],
+            vec![0.0, 0.0, 0.0],
+        ];
+        let ratings = fit_elo(&games, &points);
+        assert!((ratings[0].elo - ratings[1].elo).abs() < 1e-3);
+        assert!(ratings[2].elo.is_finite());
+        assert!(ratings[2].elo < ratings[0].elo - 200.0);
+        assert!(ratings.iter().map(|r| r.elo).sum::<f64>().abs() < 1e-6);
+        // every game c played went the same way, so it is the least certain
+        assert!(ratings[2].margin > ratings[0].margin);
+    }
+}
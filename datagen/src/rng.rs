@@ -0,0 +1,36 @@
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// reseed the current thread's rng so that everything drawn from it afterwards is reproducible
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// handle to the current thread's rng
+/// everything in datagen draws from this instead of `thread_rng` so that games can be seeded
+pub fn rng() -> GameRng {
+    GameRng
+}
+
+#[derive(Clone, Copy)]
+pub struct GameRng;
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with(|rng| rng.borrow_mut().next_u32())
+    }
+    fn next_u64(&mut self) -> u64 {
+        RNG.with(|rng| rng.borrow_mut().next_u64())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        RNG.with(|rng| rng.borrow_mut().fill_bytes(dest))
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        RNG.with(|rng| rng.borrow_mut().try_fill_bytes(dest))
+    }
+}
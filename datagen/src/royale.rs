@@ -0,0 +1,177 @@
+use rand::Rng;
+
+use crate::{
+    board::{Board, Coordinate},
+    rng::rng,
+    ruleset::{Move, Ruleset},
+    standard::Standard,
+};
+
+/// standard rules, with hazards closing in from a random side every few turns
+#[derive(Clone)]
+pub struct Royale {
+    standard: Standard,
+    shrink_every_n_turns: u32,
+    hazard_damage: i32,
+}
+
+impl Ruleset for Royale {
+    fn name(&self) -> String {
+        "royale".to_string()
+    }
+    fn generate_board(&self) -> Board {
+        let mut board = self.standard.generate_board();
+        board.hazard_damage = self.hazard_damage;
+        board
+    }
+
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board {
+        self.standard.apply_moves(board, moves)
+    }
+
+    fn spawn(&self, board: &mut Board) {
+        self.standard.spawn(board);
+        self.spawn_hazards(board);
+    }
+
+    fn spawn_hazards(&self, board: &mut Board) {
+        if self.shrink_every_n_turns > 0 && board.turn.is_multiple_of(self.shrink_every_n_turns) {
+            shrink(board);
+        }
+    }
+
+    fn game_over(&self, board: &Board) -> bool {
+        self.standard.game_over(board)
+    }
+}
+
+impl Royale {
+    pub fn new(width: u32, height: u32, num_players: u8, food_spawn_chance: u8) -> Royale {
+        Royale {
+            standard: Standard::new(width, height, num_players, food_spawn_chance),
+            shrink_every_n_turns: 25,
+            hazard_damage: 14,
+        }
+    }
+
+    /// override the amount of food that is always kept on the board
+    pub fn with_minimum_food(mut self, minimum_food: u32) -> Royale {
+        self.standard = self.standard.with_minimum_food(minimum_food);
+        self
+    }
+
+    pub fn with_shrink_every_n_turns(mut self, shrink_every_n_turns: u32) -> Royale {
+        self.shrink_every_n_turns = shrink_every_n_turns;
+        self
+    }
+
+    pub fn with_hazard_damage(mut self, hazard_damage: i32) -> Royale {
+        self.hazard_damage = hazard_damage;
+        self
+    }
+}
+
+/// move one randomly chosen edge of the safe area in by one and fill everything outside it with hazards
+/// hazards inside the safe area, like the walls of a map, stay where they are
+fn shrink(board: &mut Board) {
+    let safe = (0..board.width as i32)
+        .flat_map(|x| (0..board.height as i32).map(move |y| Coordinate::new(x, y)))
+        .filter(|c| !board.hazards.contains(c))
+        .collect::<Vec<Coordinate>>();
+    if safe.is_empty() {
+        return;
+    }
+    let mut min_x = safe.iter().map(|c| c.x).min().unwrap();
+    let mut max_x = safe.iter().map(|c| c.x).max().unwrap();
+    let mut min_y = safe.iter().map(|c| c.y).min().unwrap();
+    let mut max_y = safe.iter().map(|c| c.y).max().unwrap();
+    match rng().gen_range(0..4) {
+        0 => min_x += 1,
+        1 => max_x -= 1,
+        2 => min_y += 1,
+        _ => max_y -= 1,
+    }
+    let outside = |c: &Coordinate| c.x < min_x || c.x > max_x || c.y < min_y || c.y > max_y;
+    let inside = board
+        .hazards
+        .iter()
+        .filter(|c| !outside(c))
+        .cloned()
+        .collect::<Vec<Coordinate>>();
+    board.hazards = (0..board.width as i32)
+        .flat_map(|x| (0..board.height as i32).map(move |y| Coordinate::new(x, y)))
+        .filter(outside)
+        .chain(inside)
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Direction, Snake};
+
+    fn board(health: i8) -> Board {
+        Board {
+            food: vec![],
+            snakes: vec![Snake::new(
+                vec![
+                    Coordinate::new(5, 5),
+                    Coordinate::new(5, 4),
+                    Coordinate::new(5, 3),
+                ],
+                health,
+                0,
+            )],
+            hazards: vec![],
+            hazard_damage: 14,
+            width: 11,
+            height: 11,
+            turn: 0,
+        }
+    }
+
+    #[test]
+    fn shrinks_every_n_turns() {
+        let ruleset = Royale::new(11, 11, 1, 0)
+            .with_minimum_food(0)
+            .with_shrink_every_n_turns(3);
+        let mut board = board(100);
+        let mut hazards = vec![];
+        for _ in 0..6 {
+            board = ruleset.apply_moves(board, &[Move::new(Direction::Up, 0)]);
+            ruleset.spawn(&mut board);
+            hazards.push(board.hazards.len());
+        }
+        // one edge of 11 on turn 3, then either another 11 or 10 once the first edge is gone
+        assert_eq!(hazards[..3], [0, 0, 11]);
+        assert_eq!(hazards[3..5], [11, 11]);
+        assert!(hazards[5] == 21 || hazards[5] == 22);
+    }
+
+    #[test]
+    fn hazards_hurt_after_starving_and_before_eating() {
+        let ruleset = Royale::new(11, 11, 1, 0)
+            .with_minimum_food(0)
+            .with_shrink_every_n_turns(0)
+            .with_hazard_damage(14);
+        assert_eq!(ruleset.generate_board().hazard_damage, 14);
+
+        let mut hurt = board(50);
+        hurt.hazards = vec![Coordinate::new(5, 6)];
+        let hurt = ruleset.apply_moves(hurt, &[Move::new(Direction::Up, 0)]);
+        assert_eq!(hurt.snakes[0].health, 50 - 1 - 14);
+
+        // the damage that would have killed it doesnt, because eating comes after
+        let mut fed = board(15);
+        fed.hazards = vec![Coordinate::new(5, 6)];
+        fed.food = vec![Coordinate::new(5, 6)];
+        let fed = ruleset.apply_moves(fed, &[Move::new(Direction::Up, 0)]);
+        assert!(fed.snakes[0].alive);
+        assert_eq!(fed.snakes[0].health, 100);
+
+        let mut starved = board(15);
+        starved.hazards = vec![Coordinate::new(5, 6)];
+        let starved = ruleset.apply_moves(starved, &[Move::new(Direction::Up, 0)]);
+        assert!(!starved.snakes[0].alive);
+    }
+}
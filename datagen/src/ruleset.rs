@@ -1,13 +1,135 @@
-use crate::board::{Board, Direction};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Direction},
+    constrictor::Constrictor,
+    royale::Royale,
+    solo::Solo,
+    standard::Standard,
+    wrapped::Wrapped,
+};
 
 pub trait Ruleset {
     fn name(&self) -> String;
+    /// the map the game is played on
+    fn map_name(&self) -> String {
+        "standard".to_string()
+    }
     fn generate_board(&self) -> Board;
-    fn step_board(&self, board: Board, moves: Vec<Move>) -> Board;
+    /// the part of a turn that only depends on the moves, so that it can be replayed
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board;
+    /// the random part of a turn, like spawning food or moving hazards
+    fn spawn(&self, board: &mut Board);
+    /// just the hazard part of `spawn`, for maps that place their own food
+    fn spawn_hazards(&self, _board: &mut Board) {}
     fn game_over(&self, board: &Board) -> bool;
 }
 
+impl<R: Ruleset + ?Sized> Ruleset for Box<R> {
+    fn name(&self) -> String {
+        (**self).name()
+    }
+    fn map_name(&self) -> String {
+        (**self).map_name()
+    }
+    fn generate_board(&self) -> Board {
+        (**self).generate_board()
+    }
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board {
+        (**self).apply_moves(board, moves)
+    }
+    fn spawn(&self, board: &mut Board) {
+        (**self).spawn(board)
+    }
+    fn spawn_hazards(&self, board: &mut Board) {
+        (**self).spawn_hazards(board)
+    }
+    fn game_over(&self, board: &Board) -> bool {
+        (**self).game_over(board)
+    }
+}
+
+impl<R: Ruleset + ?Sized> Ruleset for &R {
+    fn name(&self) -> String {
+        (**self).name()
+    }
+    fn map_name(&self) -> String {
+        (**self).map_name()
+    }
+    fn generate_board(&self) -> Board {
+        (**self).generate_board()
+    }
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board {
+        (**self).apply_moves(board, moves)
+    }
+    fn spawn(&self, board: &mut Board) {
+        (**self).spawn(board)
+    }
+    fn spawn_hazards(&self, board: &mut Board) {
+        (**self).spawn_hazards(board)
+    }
+    fn game_over(&self, board: &Board) -> bool {
+        (**self).game_over(board)
+    }
+}
+
+/// the parts of a ruleset that can be tuned, named after the official game settings
 #[derive(Clone, Copy)]
+pub struct Settings {
+    /// percent chance of food spawning each turn
+    pub food_spawn_chance: u8,
+    /// food is always topped up to at least this much
+    pub minimum_food: u32,
+    /// royale moves one edge of the safe area in this often, 0 never shrinks it
+    pub shrink_every_n_turns: u32,
+    /// health royale takes away for every turn spent in a hazard
+    pub hazard_damage: i32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            food_spawn_chance: 15,
+            minimum_food: 1,
+            shrink_every_n_turns: 25,
+            hazard_damage: 14,
+        }
+    }
+}
+
+/// look up a ruleset by its official game type name
+pub fn from_name(
+    name: &str,
+    width: u32,
+    height: u32,
+    num_players: u8,
+    settings: &Settings,
+) -> Option<Box<dyn Ruleset + Send + Sync>> {
+    match name {
+        "standard" => Some(Box::new(
+            Standard::new(width, height, num_players, settings.food_spawn_chance)
+                .with_minimum_food(settings.minimum_food),
+        )),
+        "wrapped" => Some(Box::new(
+            Wrapped::new(width, height, num_players, settings.food_spawn_chance)
+                .with_minimum_food(settings.minimum_food),
+        )),
+        "constrictor" => Some(Box::new(Constrictor::new(width, height, num_players))),
+        "solo" => Some(Box::new(
+            Solo::new(width, height, num_players, settings.food_spawn_chance)
+                .with_minimum_food(settings.minimum_food),
+        )),
+        "royale" => Some(Box::new(
+            Royale::new(width, height, num_players, settings.food_spawn_chance)
+                .with_minimum_food(settings.minimum_food)
+                .with_shrink_every_n_turns(settings.shrink_every_n_turns)
+                .with_hazard_damage(settings.hazard_damage),
+        )),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Move {
     pub idx: usize,
     pub direction: Direction,
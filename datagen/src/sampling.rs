@@ -0,0 +1,32 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use sampler::Sample;
+
+use crate::{
+    book::position_key,
+    export::{LabeledPosition, Outcome},
+};
+
+impl Sample for LabeledPosition {
+    fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        position_key(&self.board, 0).hash(&mut hasher);
+        for snake in self.board.snakes.iter().filter(|s| s.alive) {
+            snake.health.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+    fn turn(&self) -> u32 {
+        self.turn
+    }
+    fn outcome(&self) -> sampler::Outcome {
+        match self.outcome {
+            Outcome::Win => sampler::Outcome::Win,
+            Outcome::Loss => sampler::Outcome::Loss,
+            Outcome::Draw => sampler::Outcome::Draw,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use brotli2::read::BrotliDecoder;
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use crate::{
+    board::{Board, Coordinate, Direction, Snake},
+    generator::{Game, Turn},
+    ruleset::{Move, Ruleset},
+    standard::Standard,
+};
+
+/// one frame of a game in the snakedump, the same format paramoptimizer reads
+#[derive(Deserialize)]
+struct Frame {
+    snakes: Vec<FrameSnake>,
+    food: Vec<FrameCoordinate>,
+}
+
+#[derive(Deserialize)]
+struct FrameSnake {
+    id: String,
+    body: Vec<FrameCoordinate>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct FrameCoordinate {
+    #[serde(rename = "X")]
+    x: i32,
+    #[serde(rename = "Y")]
+    y: i32,
+}
+
+impl From<FrameCoordinate> for Coordinate {
+    fn from(c: FrameCoordinate) -> Self {
+        Coordinate::new(c.x, c.y)
+    }
+}
+
+/// the games read out of a snakedump
+pub struct Snakedump {
+    pub games: Vec<Game>,
+    /// games that were left out because they couldnt be read or replayed
+    pub skipped: usize,
+}
+
+/// read every game out of a snakedump sqlite database
+/// games that cant be decompressed, parsed or replayed to the end are skipped
+pub fn read_snakedump(path: &Path, width: u32, height: u32) -> rusqlite::Result<Snakedump> {
+    let conn = Connection::open(path)?;
+    let mut stmt = conn.prepare("SELECT game_id, compressed_frames_json FROM snake_games")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<Vec<u8>>>(1)?))
+    })?;
+    let mut dump = Snakedump {
+        games: vec![],
+        skipped: 0,
+    };
+    for row in rows {
+        let (game_id, blob) = row?;
+        let mut contents = String::new();
+        let game = blob
+            .filter(|blob| {
+                BrotliDecoder::new(Cursor::new(blob))
+                    .read_to_string(&mut contents)
+                    .is_ok()
+            })
+            .and_then(|_| serde_json::from_str::<Vec<Frame>>(&contents).ok())
+            .and_then(|frames| to_game(game_id, &frames, width, height));
+        match game {
+            Some(game) => dump.games.push(game),
+            None => dump.skipped += 1,
+        }
+    }
+    Ok(dump)
+}
+
+/// rebuild the moves made each turn from the frames by replaying them under the standard rules
+/// none if a frame cant be reached from the one before it or the frames stop before the game is over,
+/// since either would be stored as a game that ended differently
+fn to_game(game_id: String, frames: &[Frame], width: u32, height: u32) -> Option<Game> {
+    let first = frames.first()?;
+    let ruleset = Standard::new(width, height, first.snakes.len() as u8, 0);
+    let mut board = Board {
+        food: first.food.iter().map(|c| (*c).into()).collect(),
+        snakes: first
+            .snakes
+            .iter()
+            .enumerate()
+            .map(|(idx, s)| Snake::new(s.body.iter().map(|c| (*c).into()).collect(), 100, idx))
+            .collect(),
+        hazards: vec![],
+        hazard_damage: 0,
+        width,
+        height,
+        turn: 0,
+    };
+    let mut game = Game::new(game_id, ruleset.name(), board.clone());
+    for next in &frames[1..] {
+        if ruleset.game_over(&board) {
+            break;
+        }
+        let mut moves = vec![];
+        for (idx, snake) in board.snakes.iter().enumerate() {
+            if !snake.alive {
+                continue;
+            }
+            // snakes are matched up by id since dead ones can drop out of later frames
+            let head = match next.snakes.iter().find(|s| s.id == first.snakes[idx].id) {
+                Some(s) if !s.body.is_empty() => Coordinate::from(s.body[0]),
+                _ => return None,
+            };
+            let direction = Direction::ALL
+                .into_iter()
+                .find(|d| snake.body[0] + *d == head)?;
+            moves.push(Move::new(direction, idx));
+        }
+        let applied = ruleset.apply_moves(board, &moves);
+        let mut spawned = applied.clone();
+        for food in &next.food {
+            let food = Coordinate::from(*food);
+            if !spawned.food.contains(&food) {
+                spawned.food.push(food);
+            }
+        }
+        let decision_micros = vec![0; moves.len()];
+        game.push_turn(Turn::new(moves, decision_micros, &applied, &spawned));
+        board = spawned;
+    }
+    if !ruleset.game_over(&board) {
+        return None;
+    }
+    Some(game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(snakes: &[(&str, [(i32, i32); 3])]) -> Frame {
+        Frame {
+            snakes: snakes
+                .iter()
+                .map(|(id, body)| FrameSnake {
+                    id: id.to_string(),
+                    body: body
+                        .iter()
+                        .map(|(x, y)| FrameCoordinate { x: *x, y: *y })
+                        .collect(),
+                })
+                .collect(),
+            food: vec![],
+        }
+    }
+
+    #[test]
+    fn skips_games_it_cant_follow() {
+        let start = frame(&[
+            ("a", [(5, 5), (5, 4), (5, 3)]),
+            ("b", [(0, 1), (0, 2), (0, 3)]),
+        ]);
+        // b runs off the board, which ends the game
+        let end = frame(&[
+            ("a", [(5, 6), (5, 5), (5, 4)]),
+            ("b", [(-1, 1), (0, 1), (0, 2)]),
+        ]);
+        let game = to_game("g".to_string(), &[start, end], 11, 11).unwrap();
+        assert_eq!(game.turns(), 1);
+
+        // a jumps somewhere it couldnt have moved to
+        let start = frame(&[
+            ("a", [(5, 5), (5, 4), (5, 3)]),
+            ("b", [(0, 1), (0, 2), (0, 3)]),
+        ]);
+        let jump = frame(&[
+            ("a", [(7, 7), (5, 5), (5, 4)]),
+            ("b", [(-1, 1), (0, 1), (0, 2)]),
+        ]);
+        assert!(to_game("g".to_string(), &[start, jump], 11, 11).is_none());
+
+        // the frames stop while both snakes are still alive
+        let start = frame(&[
+            ("a", [(5, 5), (5, 4), (5, 3)]),
+            ("b", [(0, 1), (0, 2), (0, 3)]),
+        ]);
+        let middle = frame(&[
+            ("a", [(5, 6), (5, 5), (5, 4)]),
+            ("b", [(1, 1), (0, 1), (0, 2)]),
+        ]);
+        assert!(to_game("g".to_string(), &[start, middle], 11, 11).is_none());
+    }
+}
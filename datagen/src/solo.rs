@@ -0,0 +1,81 @@
+use crate::{
+    board::Board,
+    ruleset::{Move, Ruleset},
+    standard::Standard,
+};
+
+/// standard rules, but the game only ends once every snake is dead
+#[derive(Clone)]
+pub struct Solo {
+    standard: Standard,
+}
+
+impl Ruleset for Solo {
+    fn name(&self) -> String {
+        "solo".to_string()
+    }
+    fn generate_board(&self) -> Board {
+        self.standard.generate_board()
+    }
+
+    fn apply_moves(&self, board: Board, moves: &[Move]) -> Board {
+        self.standard.apply_moves(board, moves)
+    }
+
+    fn spawn(&self, board: &mut Board) {
+        self.standard.spawn(board);
+    }
+
+    fn game_over(&self, board: &Board) -> bool {
+        board.snakes.iter().all(|s| !s.alive)
+    }
+}
+
+impl Solo {
+    pub fn new(width: u32, height: u32, num_players: u8, food_spawn_chance: u8) -> Solo {
+        Solo {
+            standard: Standard::new(width, height, num_players, food_spawn_chance),
+        }
+    }
+
+    /// override the amount of food that is always kept on the board
+    pub fn with_minimum_food(mut self, minimum_food: u32) -> Solo {
+        self.standard = self.standard.with_minimum_food(minimum_food);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Coordinate, Direction, Snake};
+
+    #[test]
+    fn only_over_once_the_last_snake_dies() {
+        let ruleset = Solo::new(11, 11, 1, 0);
+        let mut board = Board {
+            food: vec![],
+            snakes: vec![Snake::new(
+                vec![
+                    Coordinate::new(1, 0),
+                    Coordinate::new(2, 0),
+                    Coordinate::new(3, 0),
+                ],
+                100,
+                0,
+            )],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 0,
+        };
+        // a lone snake is a finished standard game, but not a finished solo one
+        assert!(!ruleset.game_over(&board));
+        board = ruleset.apply_moves(board, &[Move::new(Direction::Left, 0)]);
+        assert!(!ruleset.game_over(&board));
+        board = ruleset.apply_moves(board, &[Move::new(Direction::Left, 0)]);
+        assert!(!board.snakes[0].alive);
+        assert!(ruleset.game_over(&board));
+    }
+}
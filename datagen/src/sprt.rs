@@ -0,0 +1,218 @@
+use crate::{
+    generator::GetMove,
+    ratings::{elo_difference, expected_score},
+    ruleset::Ruleset,
+    tournament::play_pairing,
+};
+
+/// sequential probability ratio test between two elo hypotheses
+/// H0: the candidate is elo0 stronger than the baseline, H1: it is elo1 stronger
+#[derive(Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    /// chance of accepting H1 when H0 is true
+    pub alpha: f64,
+    /// chance of accepting H0 when H1 is true
+    pub beta: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SprtResult {
+    /// the candidate is stronger by at least elo1
+    AcceptH1,
+    /// the candidate is not stronger by more than elo0
+    AcceptH0,
+    /// ran out of games before crossing a bound
+    Inconclusive,
+}
+
+pub struct SprtOutcome {
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+    pub llr: f64,
+    pub result: SprtResult,
+}
+
+impl Sprt {
+    pub fn new(elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha,
+            beta,
+        }
+    }
+
+    /// the (lower, upper) log likelihood ratio bounds for accepting H0 and H1
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// log likelihood ratio of H1 over H0 for a win/draw/loss record
+    /// uses the normal approximation to the trinomial, like fishtest and cutechess do
+    /// one virtual win and one virtual loss are added so that one sided records still have some variance
+    pub fn llr(&self, wins: u64, draws: u64, losses: u64) -> f64 {
+        if wins + draws + losses == 0 {
+            return 0.0;
+        }
+        let (wins, draws, losses) = (wins as f64 + 1.0, draws as f64, losses as f64 + 1.0);
+        let n = wins + draws + losses;
+        let score = (wins + draws / 2.0) / n;
+        let variance =
+            (wins * (1.0 - score).powi(2) + draws * (0.5 - score).powi(2) + losses * score.powi(2))
+                / n;
+        let s0 = expected_score(self.elo0);
+        let s1 = expected_score(self.elo1);
+        n * (s1 - s0) * (2.0 * score - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn result(&self, llr: f64) -> SprtResult {
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtResult::AcceptH1
+        } else if llr <= lower {
+            SprtResult::AcceptH0
+        } else {
+            SprtResult::Inconclusive
+        }
+    }
+}
+
+/// plays a candidate against a baseline in batches until the test finishes
+pub struct SprtRunner<R>
+where
+    R: Ruleset,
+{
+    candidate: Box<dyn GetMove>,
+    baseline: Box<dyn GetMove>,
+    ruleset: R,
+    sprt: Sprt,
+    batch_size: u64,
+    max_games: u64,
+}
+
+impl<R> SprtRunner<R>
+where
+    R: Ruleset + Sync,
+{
+    /// the ruleset has to be set up for two players
+    pub fn new(
+        candidate: Box<dyn GetMove>,
+        baseline: Box<dyn GetMove>,
+        ruleset: R,
+        sprt: Sprt,
+    ) -> Self {
+        Self {
+            candidate,
+            baseline,
+            ruleset,
+            sprt,
+            batch_size: 64,
+            max_games: 100_000,
+        }
+    }
+
+    /// number of games played (in parallel) between each llr check
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(2);
+        self
+    }
+
+    /// give up after this many games even if neither bound was reached
+    pub fn with_max_games(mut self, max_games: u64) -> Self {
+        self.max_games = max_games;
+        self
+    }
+
+    pub fn run(&self) -> SprtOutcome {
+        let (lower, upper) = self.sprt.bounds();
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+        let mut llr = 0.0;
+        let mut result = SprtResult::Inconclusive;
+        while wins + draws + losses < self.max_games {
+            let games = self
+                .batch_size
+                .min(self.max_games - (wins + draws + losses));
+            let (w, d, l) = play_pairing(
+                &self.ruleset,
+                self.candidate.as_ref(),
+                self.baseline.as_ref(),
+                games,
+            );
+            wins += w;
+            draws += d;
+            losses += l;
+            llr = self.sprt.llr(wins, draws, losses);
+            let (elo, margin) = elo_difference(wins, draws, losses);
+            println!(
+                "games {}: +{} ={} -{} elo {:.1} +/- {:.1} llr {:.2} ({:.2}, {:.2})",
+                wins + draws + losses,
+                wins,
+                draws,
+                losses,
+                elo,
+                margin,
+                llr,
+                lower,
+                upper
+            );
+            result = self.sprt.result(llr);
+            if result != SprtResult::Inconclusive {
+                break;
+            }
+        }
+        SprtOutcome {
+            wins,
+            draws,
+            losses,
+            llr,
+            result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprt() -> Sprt {
+        Sprt::new(0.0, 10.0, 0.05, 0.05)
+    }
+
+    #[test]
+    fn perfect_score_accepts_h1() {
+        let test = sprt();
+        assert_eq!(test.result(test.llr(256, 0, 0)), SprtResult::AcceptH1);
+        // a single game isnt enough either way
+        assert_eq!(test.result(test.llr(1, 0, 0)), SprtResult::Inconclusive);
+    }
+
+    #[test]
+    fn perfect_loss_accepts_h0() {
+        let test = sprt();
+        assert_eq!(test.result(test.llr(0, 0, 256)), SprtResult::AcceptH0);
+        assert_eq!(test.result(test.llr(0, 0, 1)), SprtResult::Inconclusive);
+    }
+
+    #[test]
+    fn even_record_leans_to_h0() {
+        let test = sprt();
+        assert!(test.llr(100, 50, 100) < 0.0);
+        assert_eq!(test.result(test.llr(0, 1000, 0)), SprtResult::AcceptH0);
+    }
+
+    #[test]
+    fn perfect_elo_is_finite() {
+        let (elo, margin) = elo_difference(256, 0, 0);
+        assert!(elo.is_finite() && elo > 800.0, "{}", elo);
+        assert!(margin.is_finite() && margin > 0.0, "{}", margin);
+        let (elo, margin) = elo_difference(0, 0, 256);
+        assert!(elo.is_finite() && elo < -800.0, "{}", elo);
+        assert!(margin.is_finite() && margin > 0.0, "{}", margin);
+    }
+}
@@ -1,10 +1,12 @@
 use crate::{
-    board::Board,
+    board::{Board, EliminationCause},
     ruleset::{Move, Ruleset},
 };
 
+#[derive(Clone)]
 pub struct Standard {
     food_spawn_chance: u8,
+    minimum_food: u32,
     num_players: u8,
     width: u32,
     height: u32,
@@ -12,77 +14,28 @@ pub struct Standard {
 
 impl Ruleset for Standard {
     fn name(&self) -> String {
-        format!("standard_{}_players", self.num_players)
+        "standard".to_string()
     }
     fn generate_board(&self) -> Board {
         Board::create_default(self.width, self.height, self.num_players)
     }
 
-    fn step_board(&self, mut board: Board, moves: Vec<Move>) -> Board {
-        if moves.is_empty() {
-            return board;
-        }
-        // ensure the board is actually valid
-        for snake in &board.snakes {
-            if !snake.alive {
-                continue;
-            }
-            if snake.body.is_empty() {
-                panic!("Zero length snake");
-            }
-        }
-        // move the snakes
-        for snake_move in moves {
-            if !board.snakes[snake_move.idx].alive {
-                panic!("Tried to move dead snake");
-            }
-            let mut new_head = board.snakes[snake_move.idx].body[0];
-            match snake_move.direction {
-                crate::board::Direction::Up => new_head.y += 0,
-                crate::board::Direction::Down => new_head.y -= 1,
-                crate::board::Direction::Left => new_head.x -= 1,
-                crate::board::Direction::Right => new_head.y += 1,
-            }
-
-            board.snakes[snake_move.idx].body.insert(0, new_head);
-            board.snakes[snake_move.idx].body.pop();
-        }
-
-        // reduce snake health
-        for snake in &mut board.snakes {
-            if !snake.alive {
-                continue;
-            }
-            snake.health -= 1;
-        }
-
-        // feed snakes
-        let mut new_food = vec![];
-        for food in &board.food {
-            let mut eaten = false;
-            for snake in &mut board.snakes {
-                if snake.alive && snake.body[0] == *food {
-                    snake.health = 100;
-                    snake.body.push(*snake.body.last().unwrap());
-                    eaten = true;
-                }
-            }
-            if !eaten {
-                new_food.push(*food);
-            }
-        }
-        board.food = new_food;
-
-        // spawn food
-        let foodneeded = board.check_food_needed(1, 25);
-        if foodneeded > 0 {
-            board.place_food_randomly(foodneeded);
-        }
+    fn apply_moves(&self, mut board: Board, moves: &[Move]) -> Board {
+        move_snakes(&mut board, moves);
+        reduce_snake_health(&mut board);
+        damage_hazards(&mut board);
+        feed_snakes(&mut board);
+        eliminate_snakes(&mut board);
+        board.turn += 1;
         board
     }
 
+    fn spawn(&self, board: &mut Board) {
+        self.spawn_food(board);
+    }
+
     fn game_over(&self, board: &Board) -> bool {
-        false
+        board.snakes.iter().filter(|s| s.alive).count() <= 1
     }
 }
 
@@ -90,9 +43,143 @@ impl Standard {
     pub fn new(width: u32, height: u32, num_players: u8, food_spawn_chance: u8) -> Standard {
         Standard {
             food_spawn_chance,
+            minimum_food: 1,
             num_players,
             width,
             height,
         }
     }
+
+    /// override the amount of food that is always kept on the board
+    pub fn with_minimum_food(mut self, minimum_food: u32) -> Standard {
+        self.minimum_food = minimum_food;
+        self
+    }
+
+    pub fn spawn_food(&self, board: &mut Board) {
+        let foodneeded = board.check_food_needed(self.minimum_food, self.food_spawn_chance);
+        if foodneeded > 0 {
+            board.place_food_randomly(foodneeded);
+        }
+    }
+}
+
+/// move every snake that has a move one square in its direction
+pub fn move_snakes(board: &mut Board, moves: &[Move]) {
+    // ensure the board is actually valid
+    for snake in &board.snakes {
+        if !snake.alive {
+            continue;
+        }
+        if snake.body.is_empty() {
+            panic!("Zero length snake");
+        }
+    }
+    for snake_move in moves {
+        let snake = &mut board.snakes[snake_move.idx];
+        if !snake.alive {
+            panic!("Tried to move dead snake");
+        }
+        let new_head = snake.body[0] + snake_move.direction;
+        snake.body.insert(0, new_head);
+        snake.body.pop();
+    }
+}
+
+/// every snake loses one health per turn
+pub fn reduce_snake_health(board: &mut Board) {
+    for snake in &mut board.snakes {
+        if !snake.alive {
+            continue;
+        }
+        snake.health -= 1;
+    }
+}
+
+/// snakes whose head is in a hazard take extra damage, unless they are eating food there
+pub fn damage_hazards(board: &mut Board) {
+    if board.hazard_damage <= 0 {
+        return;
+    }
+    for snake in &mut board.snakes {
+        if !snake.alive {
+            continue;
+        }
+        let head = snake.body[0];
+        if board.food.contains(&head) {
+            continue;
+        }
+        // a square can be stacked with more than one hazard
+        let hits = board.hazards.iter().filter(|h| **h == head).count() as i32;
+        let health = snake.health as i32 - board.hazard_damage * hits;
+        snake.health = health.max(0) as i8;
+    }
+}
+
+/// snakes with their head on food get full health and grow by one
+pub fn feed_snakes(board: &mut Board) {
+    let mut new_food = vec![];
+    for food in &board.food {
+        let mut eaten = false;
+        for snake in &mut board.snakes {
+            if snake.alive && snake.body[0] == *food {
+                snake.health = 100;
+                snake.body.push(*snake.body.last().unwrap());
+                eaten = true;
+            }
+        }
+        if !eaten {
+            new_food.push(*food);
+        }
+    }
+    board.food = new_food;
+}
+
+/// eliminate starved and out of bounds snakes, then resolve all collisions at once
+pub fn eliminate_snakes(board: &mut Board) {
+    // the turn that is currently being played
+    let turn = board.turn + 1;
+    for idx in 0..board.snakes.len() {
+        let snake = &board.snakes[idx];
+        if !snake.alive {
+            continue;
+        }
+        if snake.health <= 0 {
+            board.snakes[idx].eliminate(EliminationCause::OutOfHealth, turn, None);
+        } else if !board.in_bounds(&snake.body[0]) {
+            board.snakes[idx].eliminate(EliminationCause::OutOfBounds, turn, None);
+        }
+    }
+
+    // collisions are checked against the board before anyone is removed
+    let mut eliminations = vec![];
+    for (idx, snake) in board.snakes.iter().enumerate() {
+        if !snake.alive {
+            continue;
+        }
+        let head = snake.body[0];
+        if snake.body[1..].contains(&head) {
+            eliminations.push((idx, EliminationCause::SelfCollision, None));
+            continue;
+        }
+        let body_collision = board.snakes.iter().enumerate().find(|(other_idx, other)| {
+            *other_idx != idx && other.alive && other.body[1..].contains(&head)
+        });
+        if let Some((other_idx, _)) = body_collision {
+            eliminations.push((idx, EliminationCause::Collision, Some(other_idx)));
+            continue;
+        }
+        let head_collision = board.snakes.iter().enumerate().find(|(other_idx, other)| {
+            *other_idx != idx
+                && other.alive
+                && other.body[0] == head
+                && snake.body.len() <= other.body.len()
+        });
+        if let Some((other_idx, _)) = head_collision {
+            eliminations.push((idx, EliminationCause::HeadToHead, Some(other_idx)));
+        }
+    }
+    for (idx, cause, by) in eliminations {
+        board.snakes[idx].eliminate(cause, turn, by);
+    }
 }
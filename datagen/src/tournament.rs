@@ -0,0 +1,278 @@
+use std::fmt;
+
+use crate::{
+    generator::{Generator, GetMove},
+    ratings::{elo_difference, fit_elo, Rating},
+    ruleset::Ruleset,
+};
+
+/// who plays who
+#[derive(Clone, Copy)]
+pub enum Schedule {
+    /// every player plays every other player
+    RoundRobin,
+    /// the player at this idx plays everyone else, nobody else plays each other
+    Gauntlet(usize),
+}
+
+/// a named player in the pool
+pub struct Entrant {
+    pub name: String,
+    pub player: Box<dyn GetMove>,
+}
+
+impl Entrant {
+    pub fn new(name: &str, player: Box<dyn GetMove>) -> Self {
+        Self {
+            name: name.to_string(),
+            player,
+        }
+    }
+}
+
+pub struct Tournament<R>
+where
+    R: Ruleset,
+{
+    entrants: Vec<Entrant>,
+    ruleset: R,
+    schedule: Schedule,
+    games_per_pairing: u64,
+}
+
+/// results of every game between two players, from the point of view of the first one
+#[derive(Clone, Copy)]
+pub struct PairingResult {
+    pub first: usize,
+    pub second: usize,
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+
+pub struct TournamentResults {
+    pub names: Vec<String>,
+    pub pairings: Vec<PairingResult>,
+    pub ratings: Vec<Rating>,
+}
+
+impl<R> Tournament<R>
+where
+    R: Ruleset + Sync,
+{
+    /// the ruleset has to be set up for two players
+    pub fn new(
+        entrants: Vec<Entrant>,
+        ruleset: R,
+        schedule: Schedule,
+        games_per_pairing: u64,
+    ) -> Self {
+        Self {
+            entrants,
+            ruleset,
+            schedule,
+            games_per_pairing,
+        }
+    }
+
+    fn pairings(&self) -> Vec<(usize, usize)> {
+        let n = self.entrants.len();
+        match self.schedule {
+            Schedule::RoundRobin => (0..n)
+                .flat_map(|a| ((a + 1)..n).map(move |b| (a, b)))
+                .collect(),
+            Schedule::Gauntlet(challenger) => (0..n)
+                .filter(|x| *x != challenger)
+                .map(|x| (challenger, x))
+                .collect(),
+        }
+    }
+
+    /// play every pairing and fit ratings to the results
+    pub fn run(&self) -> TournamentResults {
+        let mut pairings = vec![];
+        for (first, second) in self.pairings() {
+            let (wins, draws, losses) = play_pairing(
+                &self.ruleset,
+                self.entrants[first].player.as_ref(),
+                self.entrants[second].player.as_ref(),
+                self.games_per_pairing,
+            );
+            let result = PairingResult {
+                first,
+                second,
+                wins,
+                draws,
+                losses,
+            };
+            println!(
+                "{} vs {}: +{} ={} -{}",
+                self.entrants[first].name,
+                self.entrants[second].name,
+                result.wins,
+                result.draws,
+                result.losses
+            );
+            pairings.push(result);
+        }
+
+        let n = self.entrants.len();
+        let mut games = vec![vec![0.0; n]; n];
+        let mut points = vec![vec![0.0; n]; n];
+        for p in &pairings {
+            let total = (p.wins + p.draws + p.losses) as f64;
+            games[p.first][p.second] += total;
+            games[p.second][p.first] += total;
+            points[p.first][p.second] += p.wins as f64 + p.draws as f64 / 2.0;
+            points[p.second][p.first] += p.losses as f64 + p.draws as f64 / 2.0;
+        }
+
+        TournamentResults {
+            names: self.entrants.iter().map(|e| e.name.clone()).collect(),
+            pairings,
+            ratings: fit_elo(&games, &points),
+        }
+    }
+}
+
+/// play games between two players, with each taking each seat for half of them
+/// returns the wins, draws and losses of the first player
+pub fn play_pairing<R>(
+    ruleset: &R,
+    first: &dyn GetMove,
+    second: &dyn GetMove,
+    games: u64,
+) -> (u64, u64, u64)
+where
+    R: Ruleset + Sync,
+{
+    let (mut wins, mut draws, mut losses) = (0, 0, 0);
+    let first_seat_games = games.div_ceil(2);
+    // (seat order, seat of the first player, number of games)
+    for (seats, first_idx, games) in [
+        ([first, second], 0, first_seat_games),
+        ([second, first], 1, games - first_seat_games),
+    ] {
+        if games == 0 {
+            continue;
+        }
+        let players = seats.iter().map(|p| p.clone_dyn()).collect();
+        let mut generator = Generator::new(players, ruleset);
+        for game in generator.generate_data(games) {
+            match game.winner() {
+                Some(winner) if winner == first_idx => wins += 1,
+                Some(_) => losses += 1,
+                None => draws += 1,
+            }
+        }
+    }
+    (wins, draws, losses)
+}
+
+impl fmt::Display for TournamentResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.names.iter().map(|n| n.len()).max().unwrap_or(0).max(6);
+        writeln!(
+            f,
+            "{:<width$}  {:<width$}  {:>6} {:>6} {:>6} {:>7} {:>16}",
+            "player",
+            "opponent",
+            "wins",
+            "draws",
+            "losses",
+            "score",
+            "elo diff",
+            width = width
+        )?;
+        for p in &self.pairings {
+            let total = (p.wins + p.draws + p.losses).max(1) as f64;
+            let score = (p.wins as f64 + p.draws as f64 / 2.0) / total;
+            let (elo, margin) = elo_difference(p.wins, p.draws, p.losses);
+            writeln!(
+                f,
+                "{:<width$}  {:<width$}  {:>6} {:>6} {:>6} {:>6.1}% {:>7.1} +/- {:<5.1}",
+                self.names[p.first],
+                self.names[p.second],
+                p.wins,
+                p.draws,
+                p.losses,
+                score * 100.0,
+                elo,
+                margin,
+                width = width
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>4}  {:<width$}  {:>16}",
+            "rank",
+            "player",
+            "elo",
+            width = width
+        )?;
+        let mut order = (0..self.names.len()).collect::<Vec<usize>>();
+        order.sort_by(|a, b| self.ratings[*b].elo.total_cmp(&self.ratings[*a].elo));
+        for (rank, idx) in order.into_iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}  {:<width$}  {:>7.1} +/- {:<5.1}",
+                rank + 1,
+                self.names[idx],
+                self.ratings[idx].elo,
+                self.ratings[idx].margin,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{
+        board::{Board, Direction},
+        players::FloodFillPlayer,
+        standard::Standard,
+    };
+
+    /// doubles back into its own neck on its second move, remembering every seat it was given
+    #[derive(Clone)]
+    struct BackwardsPlayer {
+        seats: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl GetMove for BackwardsPlayer {
+        fn get_move(&mut self, board: &Board, you_idx: usize) -> Direction {
+            self.seats.lock().unwrap().push(you_idx);
+            if board.turn == 0 {
+                Direction::Up
+            } else {
+                Direction::Down
+            }
+        }
+        fn clone_dyn(&self) -> Box<dyn GetMove> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn pairings_swap_seats() {
+        let ruleset = Standard::new(11, 11, 2, 15);
+        let seats = Arc::new(Mutex::new(vec![]));
+        let backwards = BackwardsPlayer {
+            seats: seats.clone(),
+        };
+        let (wins, draws, losses) = play_pairing(&ruleset, &FloodFillPlayer::new(), &backwards, 5);
+        // the winner is counted for the first player from whichever seat it played
+        assert_eq!((wins, draws, losses), (5, 0, 0));
+        let played = seats.lock().unwrap().clone();
+        assert!(played.contains(&0) && played.contains(&1));
+
+        let (wins, draws, losses) = play_pairing(&ruleset, &backwards, &FloodFillPlayer::new(), 4);
+        assert_eq!((wins, draws, losses), (0, 0, 4));
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt;
+
+use crate::board::{Board, Coordinate};
+
+/// something about a board that cant happen in a real game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// a living snake has no body
+    EmptyBody { snake: usize },
+    /// two pieces of a body next to each other arent adjacent
+    Disconnected {
+        snake: usize,
+        piece: usize,
+        from: Coordinate,
+        to: Coordinate,
+    },
+    /// part of a living snake is off the board
+    OutOfBounds { snake: usize, at: Coordinate },
+    /// a living snake with health outside 1 to 100
+    Health { snake: usize, health: i8 },
+    /// food off the board
+    FoodOutOfBounds { at: Coordinate },
+    /// food under a living snake
+    FoodInBody { snake: usize, at: Coordinate },
+    /// the tail is stacked but the snake hasnt eaten for longer than a stack lasts
+    StackedTail { snake: usize, health: i8 },
+    /// a body crosses over itself somewhere other than a stacked tail
+    SelfOverlap { snake: usize, at: Coordinate },
+    /// two living snakes on the same square
+    Overlap {
+        snake: usize,
+        other: usize,
+        at: Coordinate,
+    },
+    /// more than one snake with the same id
+    DuplicateId { id: usize },
+    /// alive and eliminated dont agree
+    Elimination { snake: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::EmptyBody { snake } => write!(f, "snake {} has no body", snake),
+            ValidationError::Disconnected {
+                snake,
+                piece,
+                from,
+                to,
+            } => write!(
+                f,
+                "snake {} piece {} at {:?} isnt next to {:?}",
+                snake, piece, to, from
+            ),
+            ValidationError::OutOfBounds { snake, at } => {
+                write!(f, "snake {} is out of bounds at {:?}", snake, at)
+            }
+            ValidationError::Health { snake, health } => {
+                write!(f, "snake {} is alive with {} health", snake, health)
+            }
+            ValidationError::FoodOutOfBounds { at } => {
+                write!(f, "food out of bounds at {:?}", at)
+            }
+            ValidationError::FoodInBody { snake, at } => {
+                write!(f, "food inside snake {} at {:?}", snake, at)
+            }
+            ValidationError::StackedTail { snake, health } => write!(
+                f,
+                "snake {} has a stacked tail with {} health",
+                snake, health
+            ),
+            ValidationError::SelfOverlap { snake, at } => {
+                write!(f, "snake {} overlaps itself at {:?}", snake, at)
+            }
+            ValidationError::Overlap { snake, other, at } => {
+                write!(f, "snakes {} and {} overlap at {:?}", snake, other, at)
+            }
+            ValidationError::DuplicateId { id } => {
+                write!(f, "more than one snake has id {}", id)
+            }
+            ValidationError::Elimination { snake } => {
+                write!(f, "snake {} alive flag doesnt match its elimination", snake)
+            }
+        }
+    }
+}
+
+/// whether two squares are next to each other, across the edges too on a wrapped board
+fn adjacent(board: &Board, a: &Coordinate, b: &Coordinate, wrapped: bool) -> bool {
+    let dx = (a.x - b.x).abs();
+    let dy = (a.y - b.y).abs();
+    let dx = if wrapped {
+        dx.min(board.width as i32 - dx)
+    } else {
+        dx
+    };
+    let dy = if wrapped {
+        dy.min(board.height as i32 - dy)
+    } else {
+        dy
+    };
+    dx + dy == 1
+}
+
+/// check the board for everything that should always hold, returning every problem found
+pub fn validate(board: &Board, wrapped: bool) -> Result<(), Vec<ValidationError>> {
+    let mut errors = vec![];
+    let most_hazards = board
+        .hazards
+        .iter()
+        .map(|h| board.hazards.iter().filter(|other| *other == h).count())
+        .max()
+        .unwrap_or(0);
+    let worst_move = 1 + board.hazard_damage.max(0) * most_hazards as i32;
+    for (idx, snake) in board.snakes.iter().enumerate() {
+        if board.snakes[..idx].iter().any(|other| other.id == snake.id) {
+            errors.push(ValidationError::DuplicateId { id: snake.id });
+        }
+        if snake.alive == snake.eliminated.is_some() {
+            errors.push(ValidationError::Elimination { snake: idx });
+        }
+        // dead snakes are left where they died, which can be anywhere
+        if !snake.alive {
+            continue;
+        }
+        if snake.body.is_empty() {
+            errors.push(ValidationError::EmptyBody { snake: idx });
+            continue;
+        }
+        if snake.health <= 0 || snake.health > 100 {
+            errors.push(ValidationError::Health {
+                snake: idx,
+                health: snake.health,
+            });
+        }
+        for piece in &snake.body {
+            if !board.in_bounds(piece) {
+                errors.push(ValidationError::OutOfBounds {
+                    snake: idx,
+                    at: *piece,
+                });
+            }
+        }
+
+        // the tail can be stacked, everywhere else every piece is on its own square
+        let mut stacked = snake.body.len();
+        while stacked > 1 && snake.body[stacked - 1] == snake.body[stacked - 2] {
+            stacked -= 1;
+        }
+        // a stack only lasts a move past the start or the last meal, so the snake
+        // can have lost at most one move of health since it was last full
+        if stacked < snake.body.len() && (snake.health as i32) < 100 - worst_move {
+            errors.push(ValidationError::StackedTail {
+                snake: idx,
+                health: snake.health,
+            });
+        }
+        for piece in 1..stacked {
+            let (from, to) = (snake.body[piece - 1], snake.body[piece]);
+            if !adjacent(board, &from, &to, wrapped) {
+                errors.push(ValidationError::Disconnected {
+                    snake: idx,
+                    piece,
+                    from,
+                    to,
+                });
+            }
+            if snake.body[..piece].contains(&to) {
+                errors.push(ValidationError::SelfOverlap { snake: idx, at: to });
+            }
+        }
+
+        for food in &board.food {
+            if snake.body.contains(food) {
+                errors.push(ValidationError::FoodInBody {
+                    snake: idx,
+                    at: *food,
+                });
+            }
+        }
+        for (other_idx, other) in board.snakes.iter().enumerate().skip(idx + 1) {
+            if !other.alive {
+                continue;
+            }
+            if let Some(at) = snake.body.iter().find(|piece| other.body.contains(piece)) {
+                errors.push(ValidationError::Overlap {
+                    snake: idx,
+                    other: other_idx,
+                    at: *at,
+                });
+            }
+        }
+    }
+    for food in &board.food {
+        if !board.in_bounds(food) {
+            errors.push(ValidationError::FoodOutOfBounds { at: *food });
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{EliminationCause, Snake};
+
+    fn snake(squares: &[(i32, i32)], health: i8, id: usize) -> Snake {
+        let body = squares.iter().map(|(x, y)| Coordinate::new(*x, *y));
+        Snake::new(body.collect(), health, id)
+    }
+
+    /// two snakes in the middle of a game with nothing wrong
+    fn board() -> Board {
+        Board {
+            food: vec![Coordinate::new(5, 5)],
+            snakes: vec![
+                snake(&[(1, 1), (1, 2), (1, 3)], 90, 0),
+                snake(&[(8, 8), (8, 7), (8, 6)], 80, 1),
+            ],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 20,
+        }
+    }
+
+    /// the errors validating a tweaked copy of the board turns up
+    fn errors(tweak: impl FnOnce(&mut Board)) -> Vec<ValidationError> {
+        let mut board = board();
+        tweak(&mut board);
+        validate(&board, false).err().unwrap_or_default()
+    }
+
+    #[test]
+    fn real_boards_pass() {
+        assert_eq!(errors(|_| {}), vec![]);
+    }
+
+    #[test]
+    fn empty_body() {
+        assert_eq!(
+            errors(|b| b.snakes[1].body.clear()),
+            vec![ValidationError::EmptyBody { snake: 1 }]
+        );
+    }
+
+    #[test]
+    fn disconnected() {
+        assert_eq!(
+            errors(|b| b.snakes[0].body[2] = Coordinate::new(2, 3)),
+            vec![ValidationError::Disconnected {
+                snake: 0,
+                piece: 2,
+                from: Coordinate::new(1, 2),
+                to: Coordinate::new(2, 3),
+            }]
+        );
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let snake = snake(&[(0, 0), (-1, 0), (-2, 0)], 90, 0);
+        assert_eq!(
+            errors(|b| b.snakes[0] = snake),
+            vec![
+                ValidationError::OutOfBounds {
+                    snake: 0,
+                    at: Coordinate::new(-1, 0)
+                },
+                ValidationError::OutOfBounds {
+                    snake: 0,
+                    at: Coordinate::new(-2, 0)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn wrapped_boards_join_across_the_edge() {
+        let mut board = board();
+        board.snakes[0] = snake(&[(0, 1), (10, 1), (9, 1)], 90, 0);
+        assert!(validate(&board, true).is_ok());
+        assert_eq!(
+            validate(&board, false).unwrap_err(),
+            vec![ValidationError::Disconnected {
+                snake: 0,
+                piece: 1,
+                from: Coordinate::new(0, 1),
+                to: Coordinate::new(10, 1),
+            }]
+        );
+    }
+
+    #[test]
+    fn health() {
+        assert_eq!(
+            errors(|b| b.snakes[1].health = 0),
+            vec![ValidationError::Health {
+                snake: 1,
+                health: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn food_out_of_bounds() {
+        assert_eq!(
+            errors(|b| b.food.push(Coordinate::new(11, 5))),
+            vec![ValidationError::FoodOutOfBounds {
+                at: Coordinate::new(11, 5)
+            }]
+        );
+    }
+
+    #[test]
+    fn food_in_body() {
+        assert_eq!(
+            errors(|b| b.food.push(Coordinate::new(8, 7))),
+            vec![ValidationError::FoodInBody {
+                snake: 1,
+                at: Coordinate::new(8, 7)
+            }]
+        );
+    }
+
+    #[test]
+    fn stacked_tail() {
+        let stacked = snake(&[(1, 1), (1, 2), (1, 2)], 90, 0);
+        assert_eq!(
+            errors(|b| b.snakes[0] = stacked.clone()),
+            vec![ValidationError::StackedTail {
+                snake: 0,
+                health: 90
+            }]
+        );
+        // a move after eating the tail is still stacked
+        let ate = snake(&[(1, 1), (1, 2), (1, 2)], 99, 0);
+        assert_eq!(errors(|b| b.snakes[0] = ate), vec![]);
+        // and a snake that walked through a hazard since can be lower than that
+        let ate = snake(&[(1, 1), (1, 2), (1, 2)], 85, 0);
+        assert_eq!(
+            errors(|b| {
+                b.snakes[0] = ate;
+                b.hazards.push(Coordinate::new(1, 1));
+                b.hazard_damage = 14;
+            }),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn self_overlap() {
+        let snake = snake(&[(1, 1), (1, 2), (2, 2), (2, 1), (1, 1), (0, 1)], 90, 0);
+        assert_eq!(
+            errors(|b| b.snakes[0] = snake),
+            vec![ValidationError::SelfOverlap {
+                snake: 0,
+                at: Coordinate::new(1, 1)
+            }]
+        );
+    }
+
+    #[test]
+    fn overlap() {
+        let snake = snake(&[(8, 9), (8, 8), (7, 8)], 90, 2);
+        assert_eq!(
+            errors(|b| b.snakes.push(snake)),
+            vec![ValidationError::Overlap {
+                snake: 1,
+                other: 2,
+                at: Coordinate::new(8, 8)
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_id() {
+        assert_eq!(
+            errors(|b| b.snakes[1].id = 0),
+            vec![ValidationError::DuplicateId { id: 0 }]
+        );
+    }
+
+    #[test]
+    fn elimination() {
+        assert_eq!(
+            errors(|b| b.snakes[1].alive = false),
+            vec![ValidationError::Elimination { snake: 1 }]
+        );
+        // dead snakes can be left anywhere, as long as they say how they died
+        let errors = errors(|b| {
+            b.snakes[1].body.clear();
+            b.snakes[1].eliminate(EliminationCause::OutOfHealth, 20, None);
+        });
+        assert_eq!(errors, vec![]);
+    }
+}
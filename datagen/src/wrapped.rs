@@ -0,0 +1,95 @@
+use crate::{
+    board::Board,
+    ruleset::{Move, Ruleset},
+    standard::{
+        damage_hazards, eliminate_snakes, feed_snakes, move_snakes, reduce_snake_health, Standard,
+    },
+};
+
+/// standard rules, except snakes leaving one side of the board come back in on the other
+#[derive(Clone)]
+pub struct Wrapped {
+    standard: Standard,
+}
+
+impl Ruleset for Wrapped {
+    fn name(&self) -> String {
+        "wrapped".to_string()
+    }
+    fn generate_board(&self) -> Board {
+        self.standard.generate_board()
+    }
+
+    fn apply_moves(&self, mut board: Board, moves: &[Move]) -> Board {
+        move_snakes(&mut board, moves);
+        board.wrap();
+        reduce_snake_health(&mut board);
+        damage_hazards(&mut board);
+        feed_snakes(&mut board);
+        eliminate_snakes(&mut board);
+        board.turn += 1;
+        board
+    }
+
+    fn spawn(&self, board: &mut Board) {
+        self.standard.spawn(board);
+    }
+
+    fn game_over(&self, board: &Board) -> bool {
+        self.standard.game_over(board)
+    }
+}
+
+impl Wrapped {
+    pub fn new(width: u32, height: u32, num_players: u8, food_spawn_chance: u8) -> Wrapped {
+        Wrapped {
+            standard: Standard::new(width, height, num_players, food_spawn_chance),
+        }
+    }
+
+    /// override the amount of food that is always kept on the board
+    pub fn with_minimum_food(mut self, minimum_food: u32) -> Wrapped {
+        self.standard = self.standard.with_minimum_food(minimum_food);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Coordinate, Direction, Snake};
+
+    #[test]
+    fn crossing_an_edge_comes_back_on_the_other_side() {
+        let board = Board {
+            food: vec![],
+            snakes: vec![Snake::new(
+                vec![
+                    Coordinate::new(10, 0),
+                    Coordinate::new(9, 0),
+                    Coordinate::new(8, 0),
+                ],
+                100,
+                0,
+            )],
+            hazards: vec![],
+            hazard_damage: 0,
+            width: 11,
+            height: 11,
+            turn: 0,
+        };
+        let ruleset = Wrapped::new(11, 11, 1, 0);
+        let board = ruleset.apply_moves(board, &[Move::new(Direction::Right, 0)]);
+        let board = ruleset.apply_moves(board, &[Move::new(Direction::Down, 0)]);
+        let snake = &board.snakes[0];
+        assert!(snake.alive);
+        assert_eq!(
+            snake.body,
+            vec![
+                Coordinate::new(0, 10),
+                Coordinate::new(0, 0),
+                Coordinate::new(10, 0)
+            ]
+        );
+    }
+}
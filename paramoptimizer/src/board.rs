@@ -33,7 +33,12 @@ impl Add for Coordinate {
 #[derive(Serialize, Deserialize, Debug, DeepSizeOf, Clone)]
 pub struct Battlesnake {
     pub body: Vec<Coordinate>,
-    id: String,
+    pub id: String,
+}
+
+/// the dump frames dont say how big the board is and every older datastore is 11x11
+fn default_size() -> u32 {
+    11
 }
 
 /// board struct
@@ -41,6 +46,13 @@ pub struct Battlesnake {
 pub struct Board {
     pub snakes: Vec<Battlesnake>,
     pub food: Vec<Coordinate>,
+    #[serde(default = "default_size")]
+    pub width: u32,
+    #[serde(default = "default_size")]
+    pub height: u32,
+    /// squares that cost extra health to stand on, older dumps and datastores dont have them
+    #[serde(default)]
+    pub hazards: Vec<Coordinate>,
 }
 
 /// game struct, holds all the boards
@@ -49,9 +61,6 @@ pub struct Board {
 pub struct Game {
     pub positions: Vec<Board>,
 }
-// number of parameters
-pub const NUM_PARAMS: usize = 5;
-
 // position
 #[derive(DeepSizeOf, Serialize, Clone, Deserialize)]
 pub struct Position {
@@ -59,20 +68,22 @@ pub struct Position {
     pub status: Status,
     /// board of the position
     pub board: Board,
-    /// my health
-    pub my_health: u8,
-    /// their health
-    pub their_health: u8,
-    /// current parameter values
-    pub param_values: [f64; NUM_PARAMS],
+    /// health of every snake, in the same order as the board's snakes
+    pub health: Vec<u8>,
+    /// current parameter values, one for each feature the datastore was extracted with
+    pub param_values: Vec<f64>,
     /// future parameter values
-    pub future_param_values: [f64; NUM_PARAMS],
-    /// occupancy bitbaord
-    pub all_bb: u128,
+    pub future_param_values: Vec<f64>,
+    /// turn the position is from, older datastores dont have it
+    #[serde(default)]
+    pub turn: u32,
+    /// game the position is from, empty in older datastores
+    #[serde(default)]
+    pub game_id: String,
 }
 
 /// Status of the board
-#[derive(Clone, Copy, DeepSizeOf, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, DeepSizeOf, Serialize, Deserialize)]
 pub enum Status {
     /// I won
     Win,
@@ -92,9 +103,31 @@ impl Into<f64> for Status {
     }
 }
 
-impl From<Coordinate> for u128 {
-    fn from(input: Coordinate) -> Self {
-        1 << (input.x + input.y * 11)
+impl Board {
+    /// is the coordinate on the board
+    pub fn in_bounds(&self, c: &Coordinate) -> bool {
+        c.x >= 0 && c.x < self.width as i32 && c.y >= 0 && c.y < self.height as i32
+    }
+
+    /// the middle square, rounded down on even sizes
+    pub fn center(&self) -> Coordinate {
+        Coordinate::new(self.width as i32 / 2, self.height as i32 / 2)
+    }
+
+    /// index of an on board coordinate into a grid of width * height squares
+    pub fn index(&self, c: &Coordinate) -> usize {
+        (c.x + c.y * self.width as i32) as usize
+    }
+
+    /// every square with a piece of snake on it
+    pub fn occupied(&self) -> Vec<bool> {
+        let mut grid = vec![false; (self.width * self.height) as usize];
+        for snake in &self.snakes {
+            for piece in snake.body.iter().filter(|p| self.in_bounds(p)) {
+                grid[self.index(piece)] = true;
+            }
+        }
+        grid
     }
 }
 
@@ -104,28 +137,25 @@ impl Battlesnake {
         // my head
         let my_head = self.body[0];
         // the other snakes
-        let other_snakes: Vec<Battlesnake> = state
-            .snakes
-            .iter()
-            .filter(|x| x.id != self.id)
-            .cloned()
-            .collect();
+        let other_snakes = state.snakes.iter().filter(|x| x.id != self.id);
 
         // if my health is 0
         if health == 0 {
             return true;
         }
         // am i out of bounds?
-        if my_head.x < 0 || my_head.x > 10 || my_head.y < 0 || my_head.y > 10 {
+        if !state.in_bounds(&my_head) {
             return true;
         }
-        // am i in the other snakes body?
-        if other_snakes[0].body[1..].contains(&my_head) {
-            return true;
-        }
-        // did I lose to head to head?
-        if other_snakes[0].body[0] == my_head && other_snakes[0].body.len() >= self.body.len() {
-            return true;
+        for other in other_snakes {
+            // am i in the other snakes body?
+            if other.body[1..].contains(&my_head) {
+                return true;
+            }
+            // did I lose to head to head?
+            if other.body[0] == my_head && other.body.len() >= self.body.len() {
+                return true;
+            }
         }
         // am I in my body?
         if self.body[1..].contains(&my_head) {
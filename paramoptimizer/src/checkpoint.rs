@@ -0,0 +1,93 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// everything a training run needs to carry on where it left off
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Checkpoint {
+    /// the optimizer that wrote it, so a run isnt resumed with a different one
+    pub method: String,
+    /// the features the weights are for, so a run isnt resumed on different ones
+    #[serde(default)]
+    pub features: Vec<String>,
+    pub weights: Vec<f64>,
+    /// steps taken so far, a pass over every parameter for texel tuning
+    pub iteration: usize,
+    /// first and second moment of the gradient, momentum only uses the first
+    pub velocity: Vec<f64>,
+    pub squared: Vec<f64>,
+    /// how far texel tuning nudges each parameter
+    pub steps: Vec<f64>,
+    /// sigmoid scaling texel tuning is using
+    pub k: f64,
+    /// training loss at the end of every epoch
+    pub loss_history: Vec<f64>,
+    /// validation loss at the end of every epoch
+    pub validation_history: Vec<f64>,
+    /// lowest validation loss so far and the weights it was reached with
+    pub best_loss: Option<f64>,
+    pub best_weights: Vec<f64>,
+    /// epochs in a row without a new best
+    pub stale: usize,
+}
+
+impl Checkpoint {
+    /// the start of a run from the given weights
+    pub fn new(method: &str, weights: Vec<f64>) -> Self {
+        Self {
+            method: method.to_string(),
+            features: vec![],
+            velocity: vec![0.0; weights.len()],
+            squared: vec![0.0; weights.len()],
+            steps: vec![],
+            k: 0.0,
+            iteration: 0,
+            loss_history: vec![],
+            validation_history: vec![],
+            best_loss: None,
+            best_weights: weights.clone(),
+            stale: 0,
+            weights,
+        }
+    }
+
+    /// record the validation loss at the end of an epoch, true once it hasnt improved for `patience` epochs
+    pub fn validate(&mut self, loss: f64, patience: usize) -> bool {
+        self.validation_history.push(loss);
+        if self.best_loss.is_none_or(|best| loss < best) {
+            self.best_loss = Some(loss);
+            self.best_weights = self.weights.clone();
+            self.stale = 0;
+        } else {
+            self.stale += 1;
+        }
+        self.stale >= patience
+    }
+
+    /// the weights validation liked best, or the latest ones if there was no validation
+    pub fn final_weights(&self) -> &[f64] {
+        if self.validation_history.is_empty() {
+            &self.weights
+        } else {
+            &self.best_weights
+        }
+    }
+
+    /// written next to path first and then moved over it, so stopping mid save keeps the last checkpoint
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let temp = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&temp)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+        fs::rename(temp, path)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
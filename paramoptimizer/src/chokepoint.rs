@@ -0,0 +1,143 @@
+use crate::{
+    board::{Board, Coordinate},
+    voronoi::neighbours,
+};
+
+/// the free squares that split the free squares around them in two if a snake moved onto them
+pub fn articulation_points(board: &Board, occupied: &[bool]) -> Vec<bool> {
+    let squares = (board.width * board.height) as usize;
+    let mut search = Search {
+        board,
+        occupied,
+        time: 0,
+        discovered: vec![None; squares],
+        low: vec![0; squares],
+        points: vec![false; squares],
+    };
+    for x in 0..board.width as i32 {
+        for y in 0..board.height as i32 {
+            let square = Coordinate::new(x, y);
+            let idx = board.index(&square);
+            if !occupied[idx] && search.discovered[idx].is_none() {
+                search.visit(square, None);
+            }
+        }
+    }
+    search.points
+}
+
+/// state of tarjan's depth first search over the free squares
+struct Search<'a> {
+    board: &'a Board,
+    occupied: &'a [bool],
+    time: u32,
+    /// when each square was first reached
+    discovered: Vec<Option<u32>>,
+    /// earliest square reachable from a square's subtree with at most one step back up
+    low: Vec<u32>,
+    points: Vec<bool>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, square: Coordinate, parent: Option<usize>) {
+        let idx = self.board.index(&square);
+        self.discovered[idx] = Some(self.time);
+        self.low[idx] = self.time;
+        self.time += 1;
+        let mut children = 0;
+        for next in neighbours(&square) {
+            if !self.board.in_bounds(&next) || self.occupied[self.board.index(&next)] {
+                continue;
+            }
+            let next_idx = self.board.index(&next);
+            match self.discovered[next_idx] {
+                None => {
+                    children += 1;
+                    self.visit(next, Some(idx));
+                    self.low[idx] = self.low[idx].min(self.low[next_idx]);
+                    // nothing below the child gets back above this square without it
+                    if parent.is_some() && self.low[next_idx] >= self.discovered[idx].unwrap() {
+                        self.points[idx] = true;
+                    }
+                }
+                Some(time) if Some(next_idx) != parent => {
+                    self.low[idx] = self.low[idx].min(time);
+                }
+                _ => {}
+            }
+        }
+        // the root splits things only if the search had to leave it more than once
+        if parent.is_none() && children > 1 {
+            self.points[idx] = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    // number of groups of free squares, by flood filling from every one
+    fn components(board: &Board, occupied: &[bool]) -> usize {
+        let mut seen = occupied.to_vec();
+        let mut count = 0;
+        for x in 0..board.width as i32 {
+            for y in 0..board.height as i32 {
+                let start = Coordinate::new(x, y);
+                if seen[board.index(&start)] {
+                    continue;
+                }
+                count += 1;
+                seen[board.index(&start)] = true;
+                let mut stack = vec![start];
+                while let Some(square) = stack.pop() {
+                    for next in neighbours(&square) {
+                        if board.in_bounds(&next) && !seen[board.index(&next)] {
+                            seen[board.index(&next)] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn matches_removing_every_square() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for size in [5, 7, 11] {
+            for _ in 0..20 {
+                let board = Board {
+                    snakes: vec![],
+                    food: vec![],
+                    width: size,
+                    height: size,
+                    hazards: vec![],
+                };
+                let occupied = (0..size * size)
+                    .map(|_| rng.gen_bool(0.35))
+                    .collect::<Vec<bool>>();
+                let points = articulation_points(&board, &occupied);
+                let before = components(&board, &occupied);
+                for idx in 0..occupied.len() {
+                    if occupied[idx] {
+                        assert!(!points[idx]);
+                        continue;
+                    }
+                    let mut without = occupied.clone();
+                    without[idx] = true;
+                    // a lone square disappears instead of splitting anything, so it isnt one
+                    assert_eq!(
+                        points[idx],
+                        components(&board, &without) > before,
+                        "square {} of {:?}",
+                        idx,
+                        occupied
+                    );
+                }
+            }
+        }
+    }
+}
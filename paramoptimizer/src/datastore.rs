@@ -0,0 +1,180 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Position, Status},
+    feature::LEGACY_FEATURES,
+};
+
+/// struct used for the datastore feature
+#[derive(Serialize, Deserialize, Clone)]
+struct Frames {
+    /// all of the frames that are being stored
+    frames: Vec<Position>,
+    /// what each of the parameter values is, older datastores dont have it
+    #[serde(default)]
+    features: Vec<String>,
+}
+
+/// a position the way two snake datastores stored it before multi snake extraction
+#[derive(Deserialize)]
+struct LegacyPosition {
+    status: Status,
+    board: Board,
+    my_health: u8,
+    their_health: u8,
+    param_values: [f64; 5],
+    future_param_values: [f64; 5],
+    /// occupancy bitboard, nothing reads it anymore
+    _all_bb: u128,
+}
+
+/// frames in the layout datastores had before multi snake extraction
+#[derive(Deserialize)]
+struct LegacyFrames {
+    frames: Vec<LegacyPosition>,
+}
+
+impl From<LegacyPosition> for Position {
+    fn from(legacy: LegacyPosition) -> Self {
+        Position {
+            status: legacy.status,
+            board: legacy.board,
+            // the first snake was always me and the second them
+            health: vec![legacy.my_health, legacy.their_health],
+            param_values: legacy.param_values.to_vec(),
+            future_param_values: legacy.future_param_values.to_vec(),
+            turn: 0,
+            game_id: String::new(),
+        }
+    }
+}
+
+/// read the feature names and frames out of a messagepack datastore file
+pub fn load(path: &Path) -> io::Result<(Vec<String>, Vec<Position>)> {
+    // open the file
+    decode(&fs::read(path)?)
+}
+
+/// the feature names and frames in the bytes of a datastore file, in either layout
+fn decode(bytes: &[u8]) -> io::Result<(Vec<String>, Vec<Position>)> {
+    // deserialize the file from messagepack to the Frames struct
+    let frames = match Frames::deserialize(&mut rmp_serde::Deserializer::new(bytes)) {
+        Ok(frames) => frames,
+        // positions in old datastores have two healths where the list of them is now
+        Err(e) => match LegacyFrames::deserialize(&mut rmp_serde::Deserializer::new(bytes)) {
+            Ok(legacy) => Frames {
+                frames: legacy.frames.into_iter().map(Position::from).collect(),
+                features: vec![],
+            },
+            Err(_) => return Err(io::Error::new(ErrorKind::InvalidData, e)),
+        },
+    };
+    // datastores from before feature names were stored all have the original five
+    let features = if frames.features.is_empty() {
+        LEGACY_FEATURES.iter().map(|f| f.to_string()).collect()
+    } else {
+        frames.features
+    };
+    Ok((features, frames.frames))
+}
+
+/// write the frames and the names of their features to a messagepack datastore file
+pub fn save(path: &Path, features: &[String], frames: &[Position]) -> io::Result<()> {
+    let mut buf = vec![];
+    // serialize this into the buf
+    Frames {
+        frames: frames.to_vec(),
+        features: features.to_vec(),
+    }
+    .serialize(&mut rmp_serde::Serializer::new(&mut buf))
+    .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+    // write the buffer into the datastore file
+    fs::write(path, buf)
+}
+
+/// read weights from a file holding a json array of numbers
+pub fn load_weights(path: &Path) -> io::Result<Vec<f64>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// write weights as a json array of numbers
+pub fn save_weights(path: &Path, weights: &[f64]) -> io::Result<()> {
+    fs::write(path, serde_json::to_string(weights)?)
+}
+
+/// write weights as a rust const array a bot can `include!`, with the feature each weight is for
+pub fn save_weights_rust(
+    path: &Path,
+    name: &str,
+    features: &[String],
+    weights: &[f64],
+) -> io::Result<()> {
+    let mut source = format!(
+        "// generated by paramoptimizer, retrain instead of editing\npub const {}: [f64; {}] = [\n",
+        name,
+        weights.len()
+    );
+    for (weight, feature) in weights.iter().zip(features) {
+        // debug formatting always keeps the decimal point, so every entry is an f64 literal
+        source.push_str(&format!("    {:?}, // {}\n", weight, feature));
+    }
+    source.push_str("];\n");
+    fs::write(path, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_baseline_datastore() {
+        // the baseline paramoptimizer doesnt compile as it is, so this was written by a release build of it
+        // with the windows import and `transfer` taken out and an exit after the save, run on three games
+        // from a test sqlite
+        let (features, frames) = decode(include_bytes!("../testdata/baseline_datastore")).unwrap();
+        assert_eq!(features, LEGACY_FEATURES.map(|f| f.to_string()));
+        assert_eq!(frames.len(), 9);
+        for frame in &frames {
+            assert_eq!(frame.board.snakes.len(), 2);
+            assert_eq!(frame.health.len(), 2);
+            assert_eq!(frame.param_values.len(), LEGACY_FEATURES.len());
+            assert_eq!((frame.board.width, frame.board.height), (11, 11));
+        }
+    }
+
+    #[test]
+    fn reads_datastore_without_game_ids() {
+        // the baseline fixture's frames saved again in the current layout, which leaves the game ids empty
+        let (features, frames) = decode(include_bytes!("../testdata/unnamed_datastore")).unwrap();
+        assert_eq!(features, LEGACY_FEATURES.map(|f| f.to_string()));
+        assert_eq!(frames.len(), 9);
+        for frame in &frames {
+            assert!(frame.game_id.is_empty());
+            assert_eq!(frame.health.len(), 2);
+        }
+    }
+
+    #[test]
+    fn rust_weights_are_a_const_array() {
+        let path = std::env::temp_dir().join(format!("paramoptimizer-{}.rs", std::process::id()));
+        let features = ["length".to_string(), "voronoi".to_string()];
+        save_weights_rust(&path, "WEIGHTS", &features, &[1.0, -0.25]).unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let expected = [
+            "// generated by paramoptimizer, retrain instead of editing",
+            "pub const WEIGHTS: [f64; 2] = [",
+            "    1.0, // length",
+            "    -0.25, // voronoi",
+            "];",
+            "",
+        ];
+        assert_eq!(source, expected.join("\n"));
+    }
+}
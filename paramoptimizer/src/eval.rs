@@ -1,155 +1,597 @@
-use crate::board::{Board, Coordinate, Position, NUM_PARAMS};
-use pathfinding::prelude::astar;
-
-// this is always from the perspective of the first snake (hacky fix, but it works)
-pub fn score(position: &Position) -> [f64; NUM_PARAMS] {
-    // me
-    let me = position.board.snakes[0].clone();
-    // them
-    let other = position.board.snakes[1].clone();
-    // the length difference between me and them
-    let length_difference = (me.body.len() - other.body.len()) as i32;
-    // my distance to center - their distance to center
-    let distance_to_center = (manhattan(&me.body[0], &Coordinate::new(6, 6))
-        - manhattan(&other.body[0], &Coordinate::new(6, 6)));
-    // my heatlh - their health
-    let health_diff = (position.my_health - position.their_health) as i32;
-    // my nearest food
-    let mut my_nearest = 0;
-    // their nearest food
-    let mut their_nearest = 0;
-    for food in &position.board.food {
-        // my path to the food
-        let my_path = astar(
-            &position.board.snakes[0].body[0],
-            |p| successors(p, &position.board, position.all_bb),
-            |p| manhattan(p, food),
-            |p| *p == *food,
-        );
-        // their path to the same food
-        let their_path = astar(
-            &position.board.snakes[1].body[0],
-            |p| successors(p, &position.board, position.all_bb),
-            |p| manhattan(p, food),
-            |p| *p == *food,
-        );
-        // my distance to the food
-        let my_dist = match my_path {
-            None => 1000,                  // if i have no path, set the path length to 1k
-            Some((path, _)) => path.len(), // otherwise set it to the length of the path
-        };
-        // their distance to the food
-        let their_dist = match their_path {
-            None => 1000,                  // if they have no path, set the path length to 1k
-            Some((path, _)) => path.len(), // otherwise set it to the length of the path
-        };
-        // give credit based on whose path is shorter
-        if my_dist < their_dist {
-            // if my path is shorter, then credit me
-            my_nearest += 1;
-        } else {
-            // if their path is shorter, then credit them
-            their_nearest += 1;
-        }
+use crate::{
+    board::Coordinate,
+    chokepoint::articulation_points,
+    feature::{Context, Feature},
+    voronoi::{neighbours, Voronoi},
+};
+
+// these are always from the perspective of the first snake
+// features about a single snake compare me to the strongest opponent, ownership is me against everyone else
+
+/// my length - their length
+pub struct LengthDifference;
+
+impl Feature for LengthDifference {
+    fn name(&self) -> &'static str {
+        "length_difference"
     }
-    // my_nearest foods - their_nearest-foods
-    let food_ownership_difference = (my_nearest - their_nearest);
-    // my owned squares
-    let mut my_squares = 0;
-    // their owned squares
-    let mut their_squares = 0;
-    // go through all the squares on the board
-    for x in 0..11 {
-        for y in 0..11 {
-            // the curent coordinate
-            let thing = &Coordinate::new(x, y);
-            // if the square is in either persons body, ignore it
-            if position.board.snakes[0].body.contains(thing)
-                || position.board.snakes[1].body.contains(thing)
-            {
-                continue;
-            }
 
-            // my path to the square
-            let my_path = astar(
-                &position.board.snakes[0].body[0],
-                |p| successors(p, &position.board, position.all_bb),
-                |p| manhattan(p, thing),
-                |p| *p == *thing,
-            );
-
-            // their path to the square
-            let their_path = astar(
-                &position.board.snakes[1].body[0],
-                |p| successors(p, &position.board, position.all_bb),
-                |p| manhattan(p, thing),
-                |p| *p == *thing,
-            );
-            // my distance to the square
-            let my_dist = match my_path {
-                None => 1000,                  // if i dont have a path, set it to 1k
-                Some((path, _)) => path.len(), // if i do, set it to the length of the path
-            };
-            let their_dist = match their_path {
-                None => 1000,                  // if they dont have a path, set it to 1k
-                Some((path, _)) => path.len(), // if they do, set to the length of the path
-            };
-            // credit based on who is closer
-            if my_dist < their_dist {
-                my_squares += 1;
+    fn description(&self) -> &'static str {
+        "my length minus the strongest opponents"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let snakes = &context.board.snakes;
+        (snakes[0].body.len() as i32 - snakes[context.strongest].body.len() as i32) as f64
+    }
+}
+
+/// my distance to center - their distance to center
+pub struct CenterDistance;
+
+impl Feature for CenterDistance {
+    fn name(&self) -> &'static str {
+        "center_distance"
+    }
+
+    fn description(&self) -> &'static str {
+        "my head's distance to the center minus the strongest opponents"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let center = board.center();
+        (manhattan(&board.snakes[0].body[0], &center)
+            - manhattan(&board.snakes[context.strongest].body[0], &center)) as f64
+    }
+}
+
+/// my health - their health
+pub struct HealthDifference;
+
+impl Feature for HealthDifference {
+    fn name(&self) -> &'static str {
+        "health_difference"
+    }
+
+    fn description(&self) -> &'static str {
+        "my health minus the strongest opponents"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let health = &context.position.health;
+        (health[0] as i32 - health[context.strongest] as i32) as f64
+    }
+}
+
+/// my nearest foods - their nearest foods
+pub struct FoodOwnership;
+
+impl Feature for FoodOwnership {
+    fn name(&self) -> &'static str {
+        "food_ownership"
+    }
+
+    fn description(&self) -> &'static str {
+        "food i get to first minus food anyone else gets to first or ties on"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let mut difference = 0;
+        for food in &board.food {
+            // give credit based on whose path is shorter
+            if context.voronoi.owner_of(board, food) == Some(0) {
+                // if my path is shorter, then credit me
+                difference += 1;
             } else {
-                their_squares += 1;
+                // if one of their paths is as short, then credit them
+                difference -= 1;
             }
         }
+        difference as f64
     }
+}
+
+/// my owned squares - their owned squares
+pub struct SquareOwnership;
 
-    // the difference between the owned squares
-    let square_ownership_difference = (my_squares - their_squares);
-
-    [
-        length_difference as f64,
-        distance_to_center as f64,
-        health_diff as f64,
-        food_ownership_difference as f64,
-        square_ownership_difference as f64,
-    ]
-}
-
-// successors for a given coordinate
-fn successors(coord: &Coordinate, board: &Board, bb: u128) -> Vec<(Coordinate, i32)> {
-    // possible successors
-    let possible = [
-        Coordinate::new(0, 1),
-        Coordinate::new(0, -1),
-        Coordinate::new(-1, 0),
-        Coordinate::new(1, 0),
-    ];
-
-    // possible ending squares
-    let mut new_possible = vec![];
-    for thing in &possible {
-        new_possible.push(*thing + *coord);
-    }
-
-    let mut out = vec![];
-    // go through each possible end square
-    for thing in &new_possible {
-        // if im oob, dont include it
-        if thing.x < 0 || thing.x > 10 || thing.y < 0 || thing.y > 10 {
-            continue;
+impl Feature for SquareOwnership {
+    fn name(&self) -> &'static str {
+        "square_ownership"
+    }
+
+    fn description(&self) -> &'static str {
+        "free squares i get to first minus free squares anyone else gets to first or ties on"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let mut difference = 0;
+        // go through all the squares on the board
+        for x in 0..board.width as i32 {
+            for y in 0..board.height as i32 {
+                // the curent coordinate
+                let thing = Coordinate::new(x, y);
+                // if the square is in anyones body, ignore it
+                if context.occupied[board.index(&thing)] {
+                    continue;
+                }
+                // credit based on who is closer
+                if context.voronoi.owner_of(board, &thing) == Some(0) {
+                    difference += 1;
+                } else {
+                    difference -= 1;
+                }
+            }
         }
-        // if the bitboard has this, then dont include it
-        if bb & u128::from(*thing) != 0 {
-            continue;
+        difference as f64
+    }
+}
+
+/// can i still get back to my tail, minus can they
+pub struct TailReachable;
+
+impl Feature for TailReachable {
+    fn name(&self) -> &'static str {
+        "tail_reachable"
+    }
+
+    fn description(&self) -> &'static str {
+        "1 if my head has a path to my tail, minus the same for the strongest opponent"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        tail_reachable(context, 0) as i32 as f64
+            - tail_reachable(context, context.strongest) as i32 as f64
+    }
+}
+
+/// chokepoints in my space - chokepoints in theirs
+pub struct Chokepoints;
+
+impl Feature for Chokepoints {
+    fn name(&self) -> &'static str {
+        "chokepoints"
+    }
+
+    fn description(&self) -> &'static str {
+        "free squares that split the board when taken that i get to first, minus ones anyone else gets to first or ties on"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let points = articulation_points(board, &context.occupied);
+        let mut difference = 0;
+        for (idx, point) in points.iter().enumerate() {
+            // only free squares someone can get to count
+            if !point || context.voronoi.distance[idx].is_none() {
+                continue;
+            }
+            if context.voronoi.owner[idx] == Some(0) {
+                difference += 1;
+            } else {
+                difference -= 1;
+            }
         }
-        // add it to the out vector
-        out.push(*thing);
+        difference as f64
+    }
+}
+
+/// my moves into too little room - their moves into too little room
+pub struct DeadEnds;
+
+impl Feature for DeadEnds {
+    fn name(&self) -> &'static str {
+        "dead_ends"
+    }
+
+    fn description(&self) -> &'static str {
+        "my moves into less room than my length, minus the strongest opponents"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        (dead_ends(context, 0) as i32 - dead_ends(context, context.strongest) as i32) as f64
     }
-    // add a weight to each one
-    out.iter().map(|p| (*p, 1)).collect()
+}
+
+/// how much room i have past my length - how much they have past theirs
+pub struct SpaceMargin;
+
+impl Feature for SpaceMargin {
+    fn name(&self) -> &'static str {
+        "space_margin"
+    }
+
+    fn description(&self) -> &'static str {
+        "free squares my head can reach minus my length, minus the same for the strongest opponent"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let margin = |snake: usize| {
+            // the head is reached too, but it isnt room to move into
+            let area = reach(context, snake).reached() as i32 - 1;
+            area - context.board.snakes[snake].body.len() as i32
+        };
+        (margin(0) - margin(context.strongest)) as f64
+    }
+}
+
+/// squares next to my head someone at least as long could move into as well
+pub struct HeadToHeadDanger;
+
+impl Feature for HeadToHeadDanger {
+    fn name(&self) -> &'static str {
+        "head_to_head_danger"
+    }
+
+    fn description(&self) -> &'static str {
+        "free squares next to my head that an opponent at least as long could also move to"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let me = &board.snakes[0];
+        let danger = free_neighbours(context, &me.body[0])
+            .filter(|square| {
+                board.snakes[1..].iter().any(|other| {
+                    other.body.len() >= me.body.len() && manhattan(&other.body[0], square) == 1
+                })
+            })
+            .count();
+        danger as f64
+    }
+}
+
+/// am i standing in hazard, minus are they
+pub struct HazardExposure;
+
+impl Feature for HazardExposure {
+    fn name(&self) -> &'static str {
+        "hazard_exposure"
+    }
+
+    fn description(&self) -> &'static str {
+        "1 if my head is in a hazard, minus the same for the strongest opponent"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let exposed = |snake: usize| board.hazards.contains(&board.snakes[snake].body[0]) as i32;
+        (exposed(0) - exposed(context.strongest)) as f64
+    }
+}
+
+/// my distance to the nearest wall - their distance to the nearest wall
+pub struct WallDistance;
+
+impl Feature for WallDistance {
+    fn name(&self) -> &'static str {
+        "wall_distance"
+    }
+
+    fn description(&self) -> &'static str {
+        "my head's distance to the nearest wall minus the strongest opponents"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        let board = context.board;
+        let distance = |snake: usize| {
+            let head = board.snakes[snake].body[0];
+            head.x
+                .min(head.y)
+                .min(board.width as i32 - 1 - head.x)
+                .min(board.height as i32 - 1 - head.y)
+        };
+        (distance(0) - distance(context.strongest)) as f64
+    }
+}
+
+/// whether the turn is odd
+pub struct TurnParity;
+
+impl Feature for TurnParity {
+    fn name(&self) -> &'static str {
+        "turn_parity"
+    }
+
+    fn description(&self) -> &'static str {
+        "1 on odd turns, 0 on even ones"
+    }
+
+    fn value(&self, context: &Context) -> f64 {
+        (context.position.turn % 2) as f64
+    }
+}
+
+// every square the snake's head can get to over free squares
+fn reach(context: &Context, snake: usize) -> Voronoi {
+    let head = context.board.snakes[snake].body[0];
+    Voronoi::from_starts(context.board, &context.occupied, &[head])
+}
+
+// the free squares next to a square
+fn free_neighbours<'a>(
+    context: &'a Context,
+    square: &Coordinate,
+) -> impl Iterator<Item = Coordinate> + 'a {
+    neighbours(square).into_iter().filter(move |next| {
+        context.board.in_bounds(next) && !context.occupied[context.board.index(next)]
+    })
+}
+
+// is the tail next to the head or to a square the head can get to
+fn tail_reachable(context: &Context, snake: usize) -> bool {
+    let reach = reach(context, snake);
+    let tail = context.board.snakes[snake].body.last().unwrap();
+    neighbours(tail)
+        .iter()
+        .any(|next| reach.distance_to(context.board, next).is_some())
+}
+
+// moves that lead into fewer free squares than the snake is long
+fn dead_ends(context: &Context, snake: usize) -> usize {
+    let body = &context.board.snakes[snake].body;
+    free_neighbours(context, &body[0])
+        .filter(|next| {
+            let room = Voronoi::from_starts(context.board, &context.occupied, &[*next]);
+            room.reached() < body.len()
+        })
+        .count()
 }
 
 // manhattan distance between two coordinates
 fn manhattan(c1: &Coordinate, c2: &Coordinate) -> i32 {
     (c1.x - c2.x).abs() + (c1.y - c2.y).abs()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Battlesnake, Board, Position, Status},
+        extract::perspective,
+        feature::FeatureSet,
+    };
+    use pathfinding::prelude::astar;
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    // the a* search every square and food used to go through, kept to check the bfs against
+    // is my path to the square shorter than every opponents
+    fn owned_by_me(board: &Board, occupied: &[bool], target: &Coordinate) -> bool {
+        let my_dist = path_length(board, occupied, &board.snakes[0].body[0], target);
+        board.snakes[1..]
+            .iter()
+            .all(|snake| my_dist < path_length(board, occupied, &snake.body[0], target))
+    }
+
+    // length of the shortest path between two squares, 1k if there isnt one
+    fn path_length(board: &Board, occupied: &[bool], from: &Coordinate, to: &Coordinate) -> usize {
+        let path = astar(
+            from,
+            |p| successors(p, board, occupied),
+            |p| manhattan(p, to),
+            |p| *p == *to,
+        );
+        match path {
+            None => 1000,                  // if there is no path, set the path length to 1k
+            Some((path, _)) => path.len(), // otherwise set it to the length of the path
+        }
+    }
+
+    // successors for a given coordinate
+    fn successors(coord: &Coordinate, board: &Board, occupied: &[bool]) -> Vec<(Coordinate, i32)> {
+        // possible successors
+        let possible = [
+            Coordinate::new(0, 1),
+            Coordinate::new(0, -1),
+            Coordinate::new(-1, 0),
+            Coordinate::new(1, 0),
+        ];
+
+        // possible ending squares
+        let mut new_possible = vec![];
+        for thing in &possible {
+            new_possible.push(*thing + *coord);
+        }
+
+        let mut out = vec![];
+        // go through each possible end square
+        for thing in &new_possible {
+            // if im oob, dont include it
+            if !board.in_bounds(thing) {
+                continue;
+            }
+            // if a snake is on it, then dont include it
+            if occupied[board.index(thing)] {
+                continue;
+            }
+            // add it to the out vector
+            out.push(*thing);
+        }
+        // add a weight to each one
+        out.iter().map(|p| (*p, 1)).collect()
+    }
+
+    /// a board with random snakes crawled out from random squares, and some food
+    fn random_position(rng: &mut StdRng, size: u32, snakes: usize) -> Position {
+        let mut board = Board {
+            snakes: vec![],
+            food: vec![],
+            width: size,
+            height: size,
+            hazards: vec![],
+        };
+        for id in 0..snakes {
+            let occupied = board.occupied();
+            let free = (0..size as i32)
+                .flat_map(|x| (0..size as i32).map(move |y| Coordinate::new(x, y)))
+                .filter(|c| !occupied[board.index(c)])
+                .collect::<Vec<Coordinate>>();
+            let mut body = vec![*free.choose(rng).unwrap()];
+            let length = rng.gen_range(3..12);
+            while body.len() < length {
+                let occupied = board.occupied();
+                let tail = *body.last().unwrap();
+                let next = successors(&tail, &board, &occupied)
+                    .into_iter()
+                    .map(|(c, _)| c)
+                    .filter(|c| !body.contains(c))
+                    .collect::<Vec<Coordinate>>();
+                match next.choose(rng) {
+                    Some(c) => body.push(*c),
+                    None => break,
+                }
+            }
+            board.snakes.push(Battlesnake {
+                body,
+                id: id.to_string(),
+            });
+        }
+        let occupied = board.occupied();
+        for _ in 0..rng.gen_range(0..6) {
+            let c = Coordinate::new(rng.gen_range(0..size as i32), rng.gen_range(0..size as i32));
+            if !occupied[board.index(&c)] && !board.food.contains(&c) {
+                board.food.push(c);
+            }
+        }
+        Position {
+            status: Status::Draw,
+            health: (0..snakes).map(|_| rng.gen_range(1..=100)).collect(),
+            board,
+            param_values: vec![],
+            future_param_values: vec![],
+            turn: 0,
+            game_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn bfs_matches_astar() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let ownership =
+            FeatureSet::from_names(&["food_ownership".to_string(), "square_ownership".to_string()])
+                .unwrap();
+        for (size, snakes) in [(7, 2), (11, 2), (11, 4), (19, 4), (19, 8)] {
+            for _ in 0..10 {
+                let position = random_position(&mut rng, size, snakes);
+                let board = &position.board;
+                let occupied = board.occupied();
+                let mut food = 0;
+                for c in &board.food {
+                    food += if owned_by_me(board, &occupied, c) {
+                        1
+                    } else {
+                        -1
+                    };
+                }
+                let voronoi = Voronoi::new(board, &occupied);
+                let mut squares = 0;
+                for x in 0..size as i32 {
+                    for y in 0..size as i32 {
+                        let c = Coordinate::new(x, y);
+                        if occupied[board.index(&c)] {
+                            continue;
+                        }
+                        squares += if owned_by_me(board, &occupied, &c) {
+                            1
+                        } else {
+                            -1
+                        };
+                        // a* paths count the head as well, so they are one longer than the distance
+                        let closest = board
+                            .snakes
+                            .iter()
+                            .map(|snake| path_length(board, &occupied, &snake.body[0], &c))
+                            .min()
+                            .unwrap();
+                        let distance = voronoi.distance[board.index(&c)].map(|d| d as usize + 1);
+                        assert_eq!(distance.unwrap_or(1000), closest);
+                    }
+                }
+                let features = ownership.score(&position);
+                assert_eq!(features[0], food as f64, "food on {:?}", board);
+                assert_eq!(features[1], squares as f64, "squares on {:?}", board);
+            }
+        }
+    }
+
+    #[test]
+    fn boxed_in_against_the_wall() {
+        // i am in the left column, with them as a wall right next to me
+        let snake = |id: &str, body: &[(i32, i32)]| Battlesnake {
+            body: body.iter().map(|(x, y)| Coordinate::new(*x, *y)).collect(),
+            id: id.to_string(),
+        };
+        let position = Position {
+            status: Status::Draw,
+            board: Board {
+                snakes: vec![
+                    snake("me", &[(0, 2), (0, 3), (0, 4)]),
+                    snake("them", &[(1, 0), (1, 1), (1, 2), (1, 3), (1, 4)]),
+                ],
+                food: vec![],
+                width: 5,
+                height: 5,
+                hazards: vec![Coordinate::new(0, 2)],
+            },
+            health: vec![100, 100],
+            param_values: vec![],
+            future_param_values: vec![],
+            turn: 3,
+            game_id: String::new(),
+        };
+        let features = FeatureSet::all();
+        let values = features.score(&position);
+        let value = |name: &str| values[features.names().iter().position(|n| n == name).unwrap()];
+        // my tail is walled in, theirs is open
+        assert_eq!(value("tail_reachable"), -1.0);
+        // both of us have one move into the two squares in the corner
+        assert_eq!(value("dead_ends"), 0.0);
+        // two squares for my three long body, all 17 free ones for their five
+        assert_eq!(value("space_margin"), -13.0);
+        assert_eq!(value("head_to_head_danger"), 0.0);
+        assert_eq!(value("hazard_exposure"), 1.0);
+        assert_eq!(value("wall_distance"), 0.0);
+        assert_eq!(value("turn_parity"), 1.0);
+    }
+
+    #[test]
+    fn features_mirror_between_perspectives() {
+        let mut rng = StdRng::seed_from_u64(11);
+        // features that compare me to my only opponent just change sign when we swap
+        let names = [
+            "length_difference",
+            "center_distance",
+            "health_difference",
+            "tail_reachable",
+            "dead_ends",
+            "space_margin",
+            "hazard_exposure",
+            "wall_distance",
+        ]
+        .map(|name| name.to_string());
+        let features = FeatureSet::from_names(&names).unwrap();
+        for _ in 0..20 {
+            let position = random_position(&mut rng, 11, 2);
+            let (board, health) = perspective(
+                &position.board,
+                &position.health,
+                &position.board.snakes[1].id,
+            )
+            .unwrap();
+            assert_eq!(board.snakes[0].id, position.board.snakes[1].id);
+            assert_eq!(health, vec![position.health[1], position.health[0]]);
+            let mirrored = Position {
+                board,
+                health,
+                ..position.clone()
+            };
+            let mine = features.score(&position);
+            let theirs = features.score(&mirrored);
+            for (name, (a, b)) in names.iter().zip(mine.iter().zip(&theirs)) {
+                assert_eq!(*a, -b, "{} on {:?}", name, position.board);
+            }
+        }
+    }
+}
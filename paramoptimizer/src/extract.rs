@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    io::{prelude::*, Cursor},
+    path::Path,
+    time::Instant,
+};
+
+use brotli2::read::BrotliDecoder;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use rusqlite::{Connection, Result};
+
+use crate::{
+    board::{Board, Game, Position, Status},
+    feature::FeatureSet,
+};
+
+/// game data as taken from the SQL db
+#[derive(Debug)]
+struct GameData {
+    /// Game id as from the bsnake servers
+    game_id: String,
+    /// brotli blob of the json game
+    compressed_frames_json: Option<Vec<u8>>,
+}
+
+/// which games to take out of the snakedump db
+pub struct Query {
+    /// raw SQL condition put after WHERE
+    pub filter: Option<String>,
+    /// most games to read
+    pub limit: Option<u64>,
+    /// how many turns ahead future_param_values are taken from
+    pub offset: usize,
+    /// size of the boards the games were played on
+    pub width: u32,
+    pub height: u32,
+    /// only score positions for the first snake instead of for every snake
+    pub first_snake_only: bool,
+}
+
+impl Query {
+    fn sql(&self) -> String {
+        // the other columns can still be filtered on, they just arent needed after that
+        let mut sql = "SELECT game_id , compressed_frames_json FROM snake_games".to_string();
+        if let Some(filter) = &self.filter {
+            sql.push_str(&format!(" WHERE {}", filter));
+        }
+        if let Some(limit) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        sql
+    }
+}
+
+/// read the games out of the db and turn them into scored positions
+pub fn extract(db: &Path, query: &Query, features: &FeatureSet) -> Result<Vec<Position>> {
+    // open connection to the sqlite db
+    let conn = Connection::open(db)?;
+    // prepare the statement to grab all of the info
+    let mut stmt = conn.prepare(&query.sql())?;
+    // get t0
+    let start = Instant::now();
+
+    println!("Getting sql data");
+    // get an iterator over all of the gamedata
+    let person_iter = stmt.query_map([], |row| {
+        Ok(GameData {
+            game_id: row.get(0)?,
+            compressed_frames_json: row.get(1)?,
+        })
+    })?;
+
+    println!("Time taken : {:?}", Instant::now() - start);
+    println!("Collecting all games from sql");
+    // get t0
+    let start = Instant::now();
+    // storage struct for all of the games
+    let mut games = vec![];
+    // go throug each game blob
+    for contents in person_iter {
+        let contents = contents?;
+        // create the decompressor for the brotli blob
+        let mut decompressor = BrotliDecoder::new(Cursor::new(
+            contents.compressed_frames_json.unwrap_or_default(),
+        ));
+        let game_id = contents.game_id;
+        // storage for the un-brotli'd blob
+        let mut contents = String::new();
+        // decompress the brotli into the contents string, skipping blobs that arent valid
+        if decompressor.read_to_string(&mut contents).is_err() {
+            continue;
+        }
+        // deserialize from json using serde_json
+        let mut game: Game = match serde_json::from_str(&contents) {
+            Ok(game) => game,
+            Err(_) => continue,
+        };
+        // games too short to have a future position for anything are no use
+        if game.positions.len() < query.offset + 3 {
+            continue;
+        }
+        // the dump doesnt store the board size
+        for position in &mut game.positions {
+            position.width = query.width;
+            position.height = query.height;
+        }
+        // every snake that started the game
+        let ids = game.positions[0]
+            .snakes
+            .iter()
+            .map(|snake| snake.id.clone())
+            .collect::<Vec<String>>();
+        if ids.is_empty() {
+            continue;
+        }
+        // health of every snake by id, everyone starts on 100
+        let mut health: HashMap<String, u8> = HashMap::new();
+        // the health of every snake in every position, in the order the board has them in
+        let mut healths = vec![];
+        // go through all of the posiitons in the game, ignoring startpos and endpos
+        for idx in 1..game.positions.len() - 1 {
+            // store food from previous position
+            let old_food = &game.positions[idx - 1].food;
+            for snake in &game.positions[idx].snakes {
+                let entry = health.entry(snake.id.clone()).or_insert(100);
+                // decrement their health
+                *entry = entry.saturating_sub(1);
+                // if the old food contains their head, set their health to 100
+                if old_food.contains(&snake.body[0]) {
+                    *entry = 100;
+                }
+            }
+            healths.push(
+                game.positions[idx]
+                    .snakes
+                    .iter()
+                    .map(|snake| health[&snake.id])
+                    .collect::<Vec<u8>>(),
+            );
+        }
+        // find the last position in the game
+        let last = game.positions.last().unwrap();
+        // a snake that isnt on the last position was eliminated before it
+        let dead = |id: &str| match last.snakes.iter().find(|snake| snake.id == id) {
+            Some(snake) => snake.dead(last, health.get(id).copied().unwrap_or(100)),
+            None => true,
+        };
+        // score the game once from every snake's point of view, or just the first snake's
+        let perspectives = if query.first_snake_only { 1 } else { ids.len() };
+        for me_id in &ids[..perspectives] {
+            // check if I am dead
+            let me_dead = dead(me_id);
+            // check if all of them are dead
+            let they_dead = ids.iter().filter(|id| *id != me_id).all(|id| dead(id));
+            // calcualte the end game status
+            let status = if me_dead && they_dead {
+                // if we are all dead, then its a draw
+                Status::Draw
+            } else if !me_dead && they_dead {
+                // if i am alive and they are dead, then I am the winner
+                Status::Win
+            } else {
+                // someone else is alive, thus i loose
+                Status::Loss
+            };
+            // storage for all positions of this game
+            let mut positions = vec![];
+            // go through all positions but startpos and endpos
+            for (idx, (position, health)) in game.positions[1..].iter().zip(&healths).enumerate() {
+                // only score positions that have someone to play against
+                if position.snakes.len() < 2 {
+                    continue;
+                }
+                // and that i am still in, with me moved to the front
+                let (board, health) = match perspective(position, health, me_id) {
+                    Some(mirrored) => mirrored,
+                    None => continue,
+                };
+                // add the current position to the positions vec
+                positions.push(Position {
+                    status,
+                    health,
+                    board,
+                    param_values: vec![],
+                    future_param_values: vec![],
+                    turn: idx as u32 + 1,
+                    game_id: game_id.clone(),
+                });
+            }
+            games.push(positions);
+        }
+    }
+    println!("Time taken : {:?}", Instant::now() - start);
+    println!("Finished adding all frames");
+    println!("Processing all frames");
+    let start = Instant::now();
+    // progress bar
+    let bar = ProgressBar::new(games.len() as u64);
+    // score all of the games
+    let offset = query.offset;
+    games.par_iter_mut().for_each(|game| {
+        let game_len = game.len();
+        for y in &mut game[..] {
+            y.param_values = features.score(y);
+        }
+        for idx in 0..game_len.saturating_sub(offset) {
+            game[idx].future_param_values = game[idx + offset].param_values.clone();
+        }
+        bar.inc(1);
+    });
+    bar.finish();
+    println!();
+    println!("Time taken : {:?}", Instant::now() - start);
+    println!("Finished evaluating all frames");
+    Ok(games
+        .iter()
+        .flat_map(|x| &x[0..x.len().saturating_sub(offset)])
+        .cloned()
+        .collect())
+}
+
+/// the board and health with the given snake first and everyone else in the same order, none if it isnt on the board
+pub fn perspective(board: &Board, health: &[u8], id: &str) -> Option<(Board, Vec<u8>)> {
+    let me = board.snakes.iter().position(|snake| snake.id == id)?;
+    let mut board = board.clone();
+    let mut health = health.to_vec();
+    board.snakes[..=me].rotate_right(1);
+    health[..=me].rotate_right(1);
+    Some((board, health))
+}
@@ -0,0 +1,131 @@
+use crate::{
+    board::{Board, Position},
+    eval,
+    voronoi::Voronoi,
+};
+
+/// the features datastores were written with before they stored feature names
+pub const LEGACY_FEATURES: [&str; 5] = [
+    "length_difference",
+    "center_distance",
+    "health_difference",
+    "food_ownership",
+    "square_ownership",
+];
+
+/// everything features tend to need about a position, worked out once and shared between them
+pub struct Context<'a> {
+    pub position: &'a Position,
+    pub board: &'a Board,
+    /// every square with a piece of snake on it
+    pub occupied: Vec<bool>,
+    /// who gets to every square first
+    pub voronoi: Voronoi,
+    /// the longest opponent, with the most health breaking ties
+    pub strongest: usize,
+}
+
+impl<'a> Context<'a> {
+    /// none if there is nobody to play against
+    pub fn new(position: &'a Position) -> Option<Self> {
+        let board = &position.board;
+        let strongest = (1..board.snakes.len())
+            .max_by_key(|idx| (board.snakes[*idx].body.len(), position.health[*idx]))?;
+        let occupied = board.occupied();
+        let voronoi = Voronoi::new(board, &occupied);
+        Some(Self {
+            position,
+            board,
+            occupied,
+            voronoi,
+            strongest,
+        })
+    }
+}
+
+/// one term of the evaluation, always from the perspective of the first snake
+pub trait Feature: Send + Sync {
+    /// what the feature is called on the command line and in datastores
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn value(&self, context: &Context) -> f64;
+}
+
+/// every feature there is, in the order they are extracted in by default
+pub fn registry() -> Vec<Box<dyn Feature>> {
+    vec![
+        Box::new(eval::LengthDifference),
+        Box::new(eval::CenterDistance),
+        Box::new(eval::HealthDifference),
+        Box::new(eval::FoodOwnership),
+        Box::new(eval::SquareOwnership),
+        Box::new(eval::TailReachable),
+        Box::new(eval::Chokepoints),
+        Box::new(eval::DeadEnds),
+        Box::new(eval::SpaceMargin),
+        Box::new(eval::HeadToHeadDanger),
+        Box::new(eval::HazardExposure),
+        Box::new(eval::WallDistance),
+        Box::new(eval::TurnParity),
+    ]
+}
+
+/// the features a position gets scored with
+pub struct FeatureSet {
+    features: Vec<Box<dyn Feature>>,
+}
+
+impl FeatureSet {
+    /// every registered feature
+    pub fn all() -> Self {
+        Self {
+            features: registry(),
+        }
+    }
+
+    /// the named features in the order given
+    pub fn from_names(names: &[String]) -> Result<Self, String> {
+        let mut registry = registry();
+        let mut features = vec![];
+        for name in names {
+            match registry.iter().position(|f| f.name() == name) {
+                Some(idx) => features.push(registry.remove(idx)),
+                None => return Err(format!("unknown or repeated feature {}", name)),
+            }
+        }
+        Ok(Self { features })
+    }
+
+    /// leave out the named features
+    pub fn without(mut self, names: &[String]) -> Self {
+        self.features
+            .retain(|f| !names.iter().any(|n| n == f.name()));
+        self
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.features.iter().map(|f| f.name().to_string()).collect()
+    }
+
+    /// the value of every feature for the position
+    pub fn score(&self, position: &Position) -> Vec<f64> {
+        match Context::new(position) {
+            Some(context) => self.features.iter().map(|f| f.value(&context)).collect(),
+            // nothing to compare against in a game on my own
+            None => vec![0.0; self.features.len()],
+        }
+    }
+}
+
+/// the positions of `wanted` in `stored`, so values stored for one feature set can be read for another
+pub fn columns(stored: &[String], wanted: &[String]) -> Result<Vec<usize>, String> {
+    wanted
+        .iter()
+        .map(|name| {
+            stored
+                .iter()
+                .position(|s| s == name)
+                .ok_or_else(|| format!("{} wasnt extracted, extract again with it", name))
+        })
+        .collect()
+}
@@ -1,268 +1,461 @@
 mod board;
+mod checkpoint;
+mod chokepoint;
+mod datastore;
 mod eval;
+mod extract;
+mod feature;
 mod optimize;
+mod sampling;
+mod split;
+mod voronoi;
 
 use crate::{
-    board::{Game, Position, Status},
-    optimize::Optimizer,
+    board::Position,
+    checkpoint::Checkpoint,
+    extract::{extract, Query},
+    feature::{columns, registry, FeatureSet},
+    optimize::{Optimizer, Update},
+    split::split_by_game,
 };
-use brotli2::read::BrotliDecoder;
-use indicatif::ProgressBar;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use rand::{thread_rng, Rng};
 use rayon::prelude::*;
-use rusqlite::{Connection, Result};
-use serde::{Deserialize, Serialize};
-extern crate rmp_serde as rmps;
-use rmps::{Deserializer, Serializer};
+use sampler::SampleArgs;
 
 use std::{
-    fs,
-    io::{prelude::*, Cursor},
-    os::windows::thread,
-    path::Path,
+    path::{Path, PathBuf},
+    process,
     time::Instant,
 };
 
-/// game data as taken from the SQL db
-#[derive(Debug)]
-struct GameData {
-    /// SQL id
-    id: i32,
-    /// Game id as from the bsnake servers
-    game_id: String,
-    /// game type
-    game_type: String,
-    /// numebr of unique snakes
-    unique_snake_count: u8,
-    /// brotli blob of the json game
-    compressed_frames_json: Option<Vec<u8>>,
+/// tunes evaluation weights on positions from stored games
+#[derive(Parser)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
 }
 
-/// struct used for the datastore feature
-#[derive(Serialize, Deserialize, Clone)]
-struct Frames {
-    /// all of the frames that are being stored
-    frames: Vec<Position>,
+#[derive(Subcommand)]
+enum Command {
+    /// read games out of a snakedump db, score them and write the frames to a datastore
+    Extract(ExtractArgs),
+    /// tune weights on the frames in a datastore
+    Train(TrainArgs),
+    /// report how well some weights fit the frames in a datastore
+    Evaluate(EvaluateArgs),
+    /// list the features that can be extracted
+    Features,
 }
-fn main() -> Result<()> {
-    // the frames to be used in the optimizer
-    let mut frames = vec![];
-    // check if we have a datastore file
-    if Path::new("./datastore").exists() {
-        // if we do then read from it
-        println!("Found old datastore, going to use");
-        // get t0
-        let start = Instant::now();
-        // open the file
-        let thing = fs::read(Path::new("./datastore")).unwrap();
-        // deserialize the file from messagepack to the Frames struct
-        let thing2 = <Frames>::deserialize(&mut rmp_serde::Deserializer::new(&thing[..])).unwrap();
-        // set frames to the Frames.positions
-        frames = thing2.frames;
-        println!("Finished getting all frames from file");
-        println!("Time taken : {:?}", Instant::now() - start);
-    } else {
-        // open connection to the sqlite db
-        let conn = Connection::open("./two_snake_snakedump.sqlite")?;
-        // prepare the statement to grab all of the info
-        let mut stmt = conn.prepare("SELECT id, game_id , game_type, unique_snake_count , compressed_frames_json FROM snake_games")?;
-        // get t0
-        let start = Instant::now();
 
-        println!("Getting sql data");
-        // get an iterator over all of the gamedata
-        let person_iter = stmt.query_map([], |row| {
-            Ok(GameData {
-                id: row.get(0)?,
-                game_id: row.get(1)?,
-                game_type: row.get(2)?,
-                unique_snake_count: row.get(3)?,
-                compressed_frames_json: row.get(4)?,
-            })
-        })?;
+#[derive(Args)]
+struct FeatureArgs {
+    /// comma separated features to use, every one extracted or registered by default
+    #[clap(long, value_parser, value_delimiter = ',')]
+    features: Vec<String>,
+    /// comma separated features to leave out
+    #[clap(long, value_parser, value_delimiter = ',')]
+    without: Vec<String>,
+}
 
-        println!("Time taken : {:?}", Instant::now() - start);
-        println!("Collecting all games from sql");
-        // get t0
-        let start = Instant::now();
-        // storage struct for all of the games
-        let mut games = vec![];
-        // go throug each game blob
-        for contents in person_iter {
-            // create the decompressor for the brotli blob
-            let mut decompressor = BrotliDecoder::new(Cursor::new(
-                contents.unwrap().compressed_frames_json.unwrap(),
-            ));
-            // storage for the un-brotli'd blob
-            let mut contents = String::new();
-            // decompress the brotli into the contents string
-            decompressor.read_to_string(&mut contents).unwrap();
-            // deserialize from json using serde_json
-            let game: Game = serde_json::from_str(&contents).unwrap();
-            // my health set to 100
-            let mut me_health = 100;
-            // their health set to 100
-            let mut they_health = 100;
-            // go through all of the posiitons in the game
-            for (idx, _) in game.positions.iter().enumerate() {
-                // ignore startpos and endpos
-                if idx == 0 || game.positions.len() - 1 == idx {
-                    continue;
-                }
-                // store food from previous position
-                let old_food = game.positions[idx - 1].food.clone();
-                // find my head
-                let my_head = game.positions[idx].snakes[0].body[0];
-                // find the other guys head
-                let other_head = game.positions[idx].snakes[1].body[0];
-                // decrement my health
-                me_health -= 1;
-                // decrement their health
-                they_health -= 1;
-                // if the old food contains my head, set my health to 100
-                if old_food.contains(&my_head) {
-                    me_health = 100;
-                }
-                // if the old food contains the other guys head, set their health to 100
-                if old_food.contains(&other_head) {
-                    they_health = 100;
-                }
-            }
-            // find the last position in the game
-            let last = game.positions.last().unwrap();
-            // check if they are dead
-            let they_dead = last.snakes[1].dead(last, they_health);
-            // check if I am dead
-            let me_dead = last.snakes[0].dead(last, me_health);
-            // calcualte the end game status
-            let status = if me_dead && they_dead {
-                // if we are both dead, then its a draw
-                Status::Draw
-            } else if !me_dead && they_dead {
-                // if i am alive and they are dead, then I am the winner
-                Status::Win
-            } else {
-                // They are alive and i am dead, thus i loose
-                Status::Loss
-            };
-            // reset health values for me and them
-            let mut me_health = 100;
-            let mut they_health = 100;
-            // storage for all positions of this game
-            let mut positions = vec![];
-            // go through all positions
-            for (idx, position) in game.positions.iter().enumerate() {
-                // ignore endpos
-                if game.positions.len() - 1 == idx {
-                    continue;
-                }
-                // ignore startpos
-                if idx == 0 {
-                    continue;
-                }
-                // find old food
-                let old_food = game.positions[idx - 1].food.clone();
-                // my head
-                let my_head = game.positions[idx].snakes[0].body[0];
-                // their head
-                let other_head = game.positions[idx].snakes[1].body[0];
-                // decrement my health
-                me_health -= 1;
-                // decrement their health
-                they_health -= 1;
-                // if old food contains my head, set my health to 100
-                if old_food.contains(&my_head) {
-                    me_health = 100;
-                }
-                // if old food contains other head, set other health to 100
-                if old_food.contains(&other_head) {
-                    they_health = 100;
-                }
-                // calcualte the bitboard
-                let mut all_bb: u128 = 0;
-                for snake in &position.snakes {
-                    for piece in &snake.body {
-                        all_bb |= u128::from(*piece);
-                    }
-                }
-                // add the current position to the positions vec
-                positions.push(Position {
-                    status,
-                    my_health: me_health,
-                    their_health: they_health,
-                    board: position.clone(),
-                    param_values: Default::default(),
-                    future_param_values: Default::default(),
-                    all_bb,
-                });
-            }
-            games.push(positions.clone());
+#[derive(Args)]
+struct ExtractArgs {
+    /// sqlite db with a snake_games table
+    #[clap(long, value_parser, default_value = "./two_snake_snakedump.sqlite")]
+    db: PathBuf,
+    /// file to write the frames to
+    #[clap(long, value_parser, default_value = "./datastore")]
+    datastore: PathBuf,
+    /// raw SQL condition on snake_games, like "unique_snake_count = 2"
+    #[clap(long = "where", value_parser)]
+    filter: Option<String>,
+    /// most games to read
+    #[clap(long, value_parser)]
+    limit: Option<u64>,
+    /// how many turns ahead the future parameter values are taken from
+    #[clap(long, value_parser, default_value_t = 6)]
+    offset: usize,
+    /// size of the boards the games were played on, the dump doesnt store it
+    #[clap(long, value_parser, default_value_t = 11)]
+    width: u32,
+    #[clap(long, value_parser, default_value_t = 11)]
+    height: u32,
+    /// only score positions for the first snake in every game, instead of once for every snake
+    #[clap(long, value_parser)]
+    first_snake_only: bool,
+    #[clap(flatten)]
+    sample: SampleArgs,
+    #[clap(flatten)]
+    features: FeatureArgs,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Method {
+    /// mini batch gradient descent on the log loss of the plain sigmoid
+    Sgd,
+    /// sgd with a running velocity
+    Momentum,
+    /// sgd with per weight step sizes
+    Adam,
+    /// texel tuning of the squared error of the k scaled sigmoid
+    Texel,
+}
+
+impl Method {
+    /// the name checkpoints are tagged with
+    fn name(self) -> &'static str {
+        match self {
+            Method::Sgd => "sgd",
+            Method::Momentum => "momentum",
+            Method::Adam => "adam",
+            Method::Texel => "texel",
         }
-        println!("Time taken : {:?}", Instant::now() - start);
-        println!("Finished adding all frames");
-        println!("Processing all frames");
-        let start = Instant::now();
-        // progress bar
-        let bar = ProgressBar::new(games.len() as u64);
-        // score all of the games
-        let OFFSET = 6;
-        games.par_iter_mut().for_each(|game| {
-            let game_len = game.len();
-            for y in &mut game[..] {
-                let param_values = eval::score(y);
-                y.param_values = param_values;
-            }
-            for idx in 0..(game_len - OFFSET) {
-                game[idx].future_param_values = game[idx + OFFSET].param_values;
-            }
-            bar.inc(1);
+    }
+}
+
+#[derive(Args)]
+struct TrainArgs {
+    /// frames written by extract
+    #[clap(long, value_parser, default_value = "./datastore")]
+    datastore: PathBuf,
+    #[clap(flatten)]
+    features: FeatureArgs,
+    #[clap(long, value_enum, default_value = "sgd")]
+    optimizer: Method,
+    #[clap(long, value_parser, default_value_t = 0.001)]
+    learning_rate: f64,
+    /// how much of the velocity the momentum optimizer keeps every step
+    #[clap(long, value_parser, default_value_t = 0.9)]
+    momentum: f64,
+    /// stop once an epoch improves the training log loss by less than this
+    #[clap(long, value_parser, default_value_t = 1e-6)]
+    tolerance: f64,
+    /// frames per step
+    #[clap(long, value_parser, default_value_t = 1000)]
+    batch_size: usize,
+    /// most steps sgd, momentum and adam take
+    #[clap(long, value_parser, default_value_t = 100_000)]
+    iterations: usize,
+    /// stop sgd, momentum and adam after this many passes over the training frames, if that comes first
+    #[clap(long, value_parser)]
+    epochs: Option<usize>,
+    /// most passes texel tuning makes over every weight, otherwise it runs until --min-step
+    #[clap(long, value_parser)]
+    passes: Option<usize>,
+    /// fraction of games held out to stop training early on
+    #[clap(long, value_parser, default_value_t = 0.1)]
+    validation: f64,
+    /// fraction of games held out to report the final loss on
+    #[clap(long, value_parser, default_value_t = 0.1)]
+    test: f64,
+    /// seed for which games go where
+    #[clap(long, value_parser, default_value_t = 0)]
+    split_seed: u64,
+    /// epochs without a better validation loss before training stops
+    #[clap(long, value_parser, default_value_t = 3)]
+    patience: usize,
+    /// sigmoid scaling used by texel tuning
+    #[clap(long, value_parser, default_value_t = 0.155)]
+    k: f64,
+    /// fit k to the starting weights before texel tuning, searching between --k-min and --k-max
+    #[clap(long, value_parser)]
+    fit_k: bool,
+    #[clap(long, value_parser, default_value_t = 0.0)]
+    k_min: f64,
+    #[clap(long, value_parser, default_value_t = 1000.0)]
+    k_max: f64,
+    /// how far texel tuning first nudges each weight
+    #[clap(long, value_parser, default_value_t = 1.0)]
+    initial_step: f64,
+    /// texel tuning stops once every weight's step is below this
+    #[clap(long, value_parser, default_value_t = 0.001)]
+    min_step: f64,
+    /// json array of starting weights, random ones are used otherwise
+    #[clap(long, value_parser)]
+    weights: Option<PathBuf>,
+    /// file to write the final weights to as a json array
+    #[clap(short, long, value_parser)]
+    output: Option<PathBuf>,
+    /// file to write the final weights to as a rust const array
+    #[clap(long, value_parser)]
+    rust_output: Option<PathBuf>,
+    /// name of the const in --rust-output
+    #[clap(long, value_parser, default_value = "EVAL_WEIGHTS")]
+    const_name: String,
+    /// file to keep the training state in, so the run can be resumed
+    #[clap(long, value_parser)]
+    checkpoint: Option<PathBuf>,
+    /// iterations between checkpoints, or passes for texel tuning
+    #[clap(long, value_parser, default_value_t = 1000)]
+    checkpoint_every: usize,
+    /// carry on from a checkpoint instead of starting over, --iterations and --passes count the ones before it too
+    #[clap(long, value_parser)]
+    resume: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct EvaluateArgs {
+    /// frames written by extract
+    #[clap(long, value_parser, default_value = "./datastore")]
+    datastore: PathBuf,
+    #[clap(flatten)]
+    features: FeatureArgs,
+    /// json array of the weights to evaluate
+    #[clap(long, value_parser)]
+    weights: PathBuf,
+    /// sigmoid scaling for the k scaled error
+    #[clap(long, value_parser, default_value_t = 0.155)]
+    k: f64,
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+/// the frames in a datastore with only the chosen features' values left in them, and the names of those features
+fn load_frames(path: &Path, args: &FeatureArgs) -> (Vec<String>, Vec<Position>) {
+    println!("Loading frames from {}", path.display());
+    // get t0
+    let start = Instant::now();
+    let (stored, mut frames) = datastore::load(path)
+        .unwrap_or_else(|e| fail(format!("could not read {}: {}", path.display(), e)));
+    println!("Time taken : {:?}", Instant::now() - start);
+    println!("Number of frames: {}", frames.len());
+    if frames.is_empty() {
+        fail(format!("no frames in {}", path.display()));
+    }
+    let mut features = if args.features.is_empty() {
+        stored.clone()
+    } else {
+        args.features.clone()
+    };
+    features.retain(|f| !args.without.contains(f));
+    if features.is_empty() {
+        fail("no features left to use".to_string());
+    }
+    // pick the chosen features' values out of every frame, in the order they were asked for
+    let columns =
+        columns(&stored, &features).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    if columns.len() != stored.len() || columns.iter().enumerate().any(|(i, c)| i != *c) {
+        frames.par_iter_mut().for_each(|frame| {
+            frame.param_values = columns.iter().map(|c| frame.param_values[*c]).collect();
+            frame.future_param_values = columns
+                .iter()
+                .map(|c| frame.future_param_values[*c])
+                .collect();
         });
-        bar.finish();
-        println!();
-        println!("Time taken : {:?}", Instant::now() - start);
-        println!("Finished evaluating all frames");
-        frames = games
-            .iter()
-            .flat_map(|x| &x[0..(x.len() - OFFSET)])
-            .cloned()
-            .collect();
+    }
+    println!("Features: {}", features.join(", "));
+    (features, frames)
+}
 
-        println!("Loading frames into datastore file");
-        let start = Instant::now();
+fn load_weights(path: &Path, features: &[String]) -> Vec<f64> {
+    let weights = datastore::load_weights(path)
+        .unwrap_or_else(|e| fail(format!("could not read {}: {}", path.display(), e)));
+    if weights.len() != features.len() {
+        fail(format!(
+            "{} has {} weights, there are {} features",
+            path.display(),
+            weights.len(),
+            features.len()
+        ));
+    }
+    weights
+}
 
-        let mut buf = vec![];
-        // serialize this into the buf
-        Frames {
-            frames: frames.clone(),
+fn main() {
+    match Cli::parse().command {
+        Command::Extract(args) => run_extract(args),
+        Command::Train(args) => run_train(args),
+        Command::Evaluate(args) => run_evaluate(args),
+        Command::Features => {
+            for feature in registry() {
+                println!("{:<24}{}", feature.name(), feature.description());
+            }
         }
-        .serialize(&mut rmp_serde::Serializer::new(&mut buf))
-        .unwrap();
-        // write the buffer into the datastore file
-        fs::write("./datastore", buf).expect("unable to write to ./datastore");
-        println!("Time taken : {:?}", Instant::now() - start);
+    }
+}
 
-        println!("Finished writing all frames to file");
+fn run_extract(args: ExtractArgs) {
+    let features = if args.features.features.is_empty() {
+        FeatureSet::all()
+    } else {
+        FeatureSet::from_names(&args.features.features).unwrap_or_else(|e| fail(e))
     }
+    .without(&args.features.without);
+    println!("Features: {}", features.names().join(", "));
+    let query = Query {
+        filter: args.filter,
+        limit: args.limit,
+        offset: args.offset,
+        width: args.width,
+        height: args.height,
+        first_snake_only: args.first_snake_only,
+    };
+    let frames = extract(&args.db, &query, &features)
+        .unwrap_or_else(|e| fail(format!("could not read {}: {}", args.db.display(), e)));
 
-    println!("Number of frames: {}", frames.len());
-    // let x = Optimizer { positions: games };
-    // let min_k = x.minimize_k(0.16, &vec![36, -41, -4, 113]);
-    // println!("min_k : {min_k}");
+    // drop repeated positions, stop the opening from dominating and even out wins and losses
+    let extracted = frames.len();
+    let frames = args.sample.sampler().apply(frames);
+    println!("Sampled {} out of {} frames", frames.len(), extracted);
 
+    println!("Loading frames into datastore file");
+    let start = Instant::now();
+    datastore::save(&args.datastore, &features.names(), &frames).unwrap_or_else(|e| {
+        fail(format!(
+            "unable to write to {}: {}",
+            args.datastore.display(),
+            e
+        ))
+    });
+    println!("Time taken : {:?}", Instant::now() - start);
+    println!("Finished writing all frames to file");
+}
+
+fn run_train(args: TrainArgs) {
+    let (features, frames) = load_frames(&args.datastore, &args.features);
+    // keep every game in one part so positions from a game cant leak between them
+    let split = split_by_game(frames, args.validation, args.test, args.split_seed);
+    println!(
+        "Train frames: {}, validation frames: {}, test frames: {}",
+        split.train.len(),
+        split.validation.len(),
+        split.test.len()
+    );
+    if split.train.is_empty() {
+        fail("no games left to train on".to_string());
+    }
+    // an epoch is enough batches to see every training frame once
+    let batches_per_epoch = split.train.len().div_ceil(args.batch_size.max(1));
+    let iterations = match (args.optimizer, args.epochs) {
+        // texel tuning counts passes over the weights instead of steps
+        (Method::Texel, _) => args.passes.unwrap_or(usize::MAX),
+        (_, Some(epochs)) => args.iterations.min(epochs * batches_per_epoch.max(1)),
+        (_, None) => args.iterations,
+    };
     // get t0
     let start = Instant::now();
+    // the texel optimizer doesnt use gradient steps at all
+    let update = match args.optimizer {
+        Method::Momentum => Update::Momentum(args.momentum),
+        Method::Adam => Update::adam(),
+        _ => Update::Sgd,
+    };
     // initialize the optimzer
-    let x = Optimizer { positions: frames };
+    let mut x = Optimizer::new(split.train)
+        .with_learning_rate(args.learning_rate)
+        .with_batch_size(args.batch_size)
+        .with_max_iterations(iterations)
+        .with_validation(split.validation, args.patience)
+        .with_tolerance(args.tolerance)
+        .with_steps(args.initial_step, args.min_step)
+        .with_update(update);
+    if let Some(path) = &args.checkpoint {
+        x = x.with_checkpoints(path.clone(), args.checkpoint_every);
+    }
+
+    let checkpoint = match &args.resume {
+        Some(path) => {
+            let checkpoint = Checkpoint::load(path)
+                .unwrap_or_else(|e| fail(format!("could not read {}: {}", path.display(), e)));
+            if checkpoint.method != args.optimizer.name() {
+                fail(format!(
+                    "{} was written by {}, not {}",
+                    path.display(),
+                    checkpoint.method,
+                    args.optimizer.name()
+                ));
+            }
+            // checkpoints from before features were named only have the weights to go on
+            if (!checkpoint.features.is_empty() && checkpoint.features != features)
+                || checkpoint.weights.len() != features.len()
+            {
+                fail(format!(
+                    "{} was trained on {}, not {}",
+                    path.display(),
+                    checkpoint.features.join(", "),
+                    features.join(", ")
+                ));
+            }
+            println!("Resuming from iteration {}", checkpoint.iteration);
+            checkpoint
+        }
+        None => {
+            let initial = match &args.weights {
+                Some(path) => load_weights(path, &features),
+                None => {
+                    let mut thread_rng = thread_rng();
+                    (0..features.len())
+                        .map(|_| thread_rng.gen_range(-1.0..1.0))
+                        .collect::<Vec<f64>>()
+                }
+            };
+            let mut checkpoint = Checkpoint::new(args.optimizer.name(), initial);
+            checkpoint.features = features.clone();
+            checkpoint.k = if args.fit_k {
+                let k = x.minimize_k(args.k_min, args.k_max, &checkpoint.weights);
+                println!("min_k : {k}");
+                k
+            } else {
+                args.k
+            };
+            checkpoint
+        }
+    };
     // add in the parameters and optimize
-    let mut thread_rng = thread_rng();
-    let new_params = x.local_optimize(
-        0.155,
-        (0..5)
-            .map(|_| thread_rng.gen_range(-1.0..1.0))
-            .collect::<Vec<f64>>(),
-    );
+    let checkpoint = match args.optimizer {
+        Method::Texel => x.local_search(checkpoint),
+        _ => x.stoch_gradient_descent(checkpoint),
+    };
+    let new_params = checkpoint.final_weights().to_vec();
+    let k = checkpoint.k;
 
-    println!("Final parameters: {:?}", new_params);
+    for (feature, weight) in features.iter().zip(&new_params) {
+        println!("{:<24}{}", feature, weight);
+    }
     println!("Time taken: {:?}", Instant::now() - start);
-    Ok(())
+    report("Train", &x, &new_params, k, &x.positions);
+    report("Validation", &x, &new_params, k, x.validation());
+    report("Test", &x, &new_params, k, &split.test);
+    if let Some(path) = &args.output {
+        datastore::save_weights(path, &new_params)
+            .unwrap_or_else(|e| fail(format!("unable to write to {}: {}", path.display(), e)));
+    }
+    if let Some(path) = &args.rust_output {
+        datastore::save_weights_rust(path, &args.const_name, &features, &new_params)
+            .unwrap_or_else(|e| fail(format!("unable to write to {}: {}", path.display(), e)));
+    }
+}
+
+/// print how well the weights fit one part of the data
+fn report(name: &str, x: &Optimizer, weights: &[f64], k: f64, positions: &[Position]) {
+    if positions.is_empty() {
+        println!("{}: no frames", name);
+        return;
+    }
+    println!(
+        "{}: log loss {}, MSE {}, texel error {}, accuracy {}",
+        name,
+        x.log_loss(weights, positions),
+        x.MSE_loss(weights, positions),
+        x.texel_error(k, weights, positions),
+        x.accuracy(weights, positions)
+    );
+}
+
+fn run_evaluate(args: EvaluateArgs) {
+    let (features, frames) = load_frames(&args.datastore, &args.features);
+    let weights = load_weights(&args.weights, &features);
+    let x = Optimizer::new(frames);
+    println!("MSE: {}", x.MSE_loss(&weights, &x.positions));
+    println!("Log loss: {}", x.log_loss(&weights, &x.positions));
+    println!("Error: {}", x.average_error(&weights, &x.positions));
+    println!(
+        "Texel error with k {}: {}",
+        args.k,
+        x.evaluation_error(args.k, &weights)
+    );
+    println!("Accuracy: {}", x.accuracy(&weights, &x.positions));
 }
@@ -1,222 +1,387 @@
-use std::f64::consts::E;
+use std::{f64::consts::E, path::PathBuf};
 
 use crate::{
-    board::Position,
-    eval::{self, score},
+    board::{Position, Status},
+    checkpoint::Checkpoint,
 };
 use rand::prelude::*;
 use rayon::prelude::*;
+/// how a gradient step turns into a change in the weights
+#[derive(Clone, Copy, Debug)]
+pub enum Update {
+    /// step straight down the gradient
+    Sgd,
+    /// keep a running velocity, decayed by the given factor every step
+    Momentum(f64),
+    /// per weight step sizes from running averages of the gradient and its square
+    Adam { beta1: f64, beta2: f64 },
+}
+
+impl Update {
+    pub fn adam() -> Self {
+        Update::Adam {
+            beta1: 0.9,
+            beta2: 0.999,
+        }
+    }
+}
+
 pub struct Optimizer {
     pub positions: Vec<Position>,
+    /// step size for gradient descent
+    learning_rate: f64,
+    /// positions per step
+    batch_size: usize,
+    /// most steps to take before stopping
+    max_iterations: usize,
+    /// positions from games held out of training, used to stop early
+    validation: Vec<Position>,
+    /// epochs without a better validation loss before training stops
+    patience: usize,
+    /// how gradient steps are applied
+    update: Update,
+    /// stop once an epoch improves the training loss by less than this
+    tolerance: f64,
+    /// how far texel tuning first nudges each parameter
+    initial_step: f64,
+    /// texel tuning stops once every step is smaller than this
+    min_step: f64,
+    /// file to write checkpoints to, and how many iterations apart
+    checkpoint: Option<(PathBuf, usize)>,
 }
 
 impl Optimizer {
-    /// entry point function for optimizing the initial guess across the dataset in self.
-    pub fn local_optimize(&self, k: f64, initial_guess: Vec<f64>) -> Vec<f64> {
-        self.stoch_gradient_descent(initial_guess)
+    pub fn new(positions: Vec<Position>) -> Self {
+        Self {
+            positions,
+            learning_rate: 0.001,
+            batch_size: 1000,
+            max_iterations: 100_000,
+            validation: vec![],
+            patience: 3,
+            update: Update::Sgd,
+            tolerance: 1e-6,
+            initial_step: 1.0,
+            min_step: 1e-3,
+            checkpoint: None,
+        }
+    }
+
+    pub fn with_learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// most gradient steps to take, or passes over every parameter for texel tuning
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// stop once the loss on these positions hasnt improved for `patience` epochs,
+    /// and return the weights it was lowest for
+    pub fn with_validation(mut self, validation: Vec<Position>, patience: usize) -> Self {
+        self.validation = validation;
+        self.patience = patience.max(1);
+        self
+    }
+
+    pub fn with_update(mut self, update: Update) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_steps(mut self, initial_step: f64, min_step: f64) -> Self {
+        self.initial_step = initial_step;
+        self.min_step = min_step;
+        self
     }
 
-    /// perceptron learning function
-    fn stoch_gradient_descent(&self, initial_guess: Vec<f64>) -> Vec<f64> {
+    /// write the training state to the file every `every` iterations, and when training ends
+    pub fn with_checkpoints(mut self, path: PathBuf, every: usize) -> Self {
+        self.checkpoint = Some((path, every.max(1)));
+        self
+    }
+
+    pub fn validation(&self) -> &[Position] {
+        &self.validation
+    }
+
+    /// mini batch gradient descent on the log loss of the plain sigmoid
+    /// carries on from the checkpoint, which is returned with where it ended up
+    pub fn stoch_gradient_descent(&self, checkpoint: Checkpoint) -> Checkpoint {
         // initialize thread rng
         let mut rng = thread_rng();
-        // initialize the weights as the initial guess.
-        let mut weights = initial_guess;
-        // iteration count
-        let mut iteration = 1;
-        // learning rate
-        let learning_rate = 0.001;
-        let batch_size = 1000;
-        let testing_amount = 10_000;
-        let testing_data = &self.positions[0..testing_amount];
-        let training_data = &self.positions[testing_amount..];
-        // loop forever
-        loop {
-            let batch: Vec<Position> = training_data
-                .choose_multiple(&mut rng, batch_size)
-                .cloned()
+        let mut c = checkpoint;
+        let training_data = &self.positions;
+        // check for convergence once every pass over the training data
+        let epoch = training_data.len().div_ceil(self.batch_size).max(1);
+        let mut last_loss = match c.loss_history.last() {
+            Some(loss) => *loss,
+            None => self.log_loss(&c.weights, training_data),
+        };
+        // loop until we converge or run out of iterations
+        while c.iteration < self.max_iterations {
+            c.iteration += 1;
+            let batch: Vec<&Position> = training_data
+                .choose_multiple(&mut rng, self.batch_size)
                 .collect();
-            let error = self.average_error(&weights, &batch[..]);
-            for x in 0..weights.len() {
-                weights[x] += learning_rate * error * transfer;
+            let gradient = self.gradient(&c.weights, &batch);
+            for (x, slope) in gradient.into_iter().enumerate() {
+                c.weights[x] -= match self.update {
+                    Update::Sgd => self.learning_rate * slope,
+                    Update::Momentum(beta) => {
+                        c.velocity[x] = beta * c.velocity[x] + slope;
+                        self.learning_rate * c.velocity[x]
+                    }
+                    Update::Adam { beta1, beta2 } => {
+                        c.velocity[x] = beta1 * c.velocity[x] + (1.0 - beta1) * slope;
+                        c.squared[x] = beta2 * c.squared[x] + (1.0 - beta2) * slope * slope;
+                        // correct for both averages starting out at zero
+                        let mean = c.velocity[x] / (1.0 - beta1.powi(c.iteration as i32));
+                        let variance = c.squared[x] / (1.0 - beta2.powi(c.iteration as i32));
+                        self.learning_rate * mean / (variance.sqrt() + 1e-8)
+                    }
+                };
             }
-            iteration += 1;
-            if iteration % 1000 == 0 {
-                // print every 10 iterations to prevent terminal spam
-                println!(
-                    "Overall MSE: {}, Weights: {:?}, iteration : {}",
-                    self.MSE_loss(&weights, &self.positions),
-                    weights,
-                    iteration
-                );
-                println!("Testing MSE: {}", self.MSE_loss(&weights, testing_data));
-                println!("Training MSE: {}", self.MSE_loss(&weights, training_data));
+            self.save_checkpoint(&c, false);
+            if c.iteration.is_multiple_of(epoch) {
+                if !self.validation.is_empty()
+                    && c.validate(self.log_loss(&c.weights, &self.validation), self.patience)
+                {
+                    println!(
+                        "Validation loss stopped improving after {} iterations",
+                        c.iteration
+                    );
+                    break;
+                }
+                let loss = self.log_loss(&c.weights, training_data);
+                c.loss_history.push(loss);
+                // a pass that made things worse isnt converging, just noisy
+                if (0.0..self.tolerance).contains(&(last_loss - loss)) {
+                    println!("Converged after {} iterations", c.iteration);
+                    break;
+                }
+                last_loss = loss;
+            }
+            if c.iteration.is_multiple_of(1000) {
+                // print every 1000 iterations to prevent terminal spam
                 println!(
-                    "Testing Error: {}",
-                    self.average_error(&weights, testing_data)
+                    "Training MSE: {}, Weights: {:?}, iteration : {}",
+                    self.MSE_loss(&c.weights, training_data),
+                    c.weights,
+                    c.iteration
                 );
+                // both losses average over the positions, which is nan without any
+                if !self.validation.is_empty() {
+                    println!(
+                        "Validation MSE: {}",
+                        self.MSE_loss(&c.weights, &self.validation)
+                    );
+                    println!(
+                        "Validation log loss: {}",
+                        self.log_loss(&c.weights, &self.validation)
+                    );
+                }
                 println!(
-                    "Training Error: {}",
-                    self.average_error(&weights, training_data)
+                    "Training log loss: {}",
+                    self.log_loss(&c.weights, training_data)
                 );
             }
         }
+        self.save_checkpoint(&c, true);
+        c
     }
-    /// compute the gradient vector
-    fn gradient(
-        &self,
-        guess: &Vec<f64>,
-        mut thread_rng: &mut ThreadRng,
-        batch_size: usize,
-    ) -> Vec<f64> {
-        // the output vector
-        let mut out = vec![];
-        // batch of positions to use
-        let batch: Vec<Position> = self
-            .positions
-            .choose_multiple(&mut thread_rng, batch_size)
-            .cloned()
-            .collect();
-        // go through each guess parameter
-        for x in 0..guess.len() {
-            // clone the weights
-            let mut new_guess = guess.clone();
-            // update the current weight by .001 (some small nonce)
-            let nonce = 0.000000000001;
-            new_guess[x] += nonce;
-            // calculate the slope of the error function
-            let evaluation_error =
-                self.average_error(&new_guess, &batch) - self.average_error(guess, &batch);
-            if evaluation_error < nonce {
-                panic!(
-                    "Evaluation error was less than {}, it was {}",
-                    nonce, evaluation_error
-                );
+
+    /// write the checkpoint if checkpoints are on and one is due, or always if `force`
+    fn save_checkpoint(&self, checkpoint: &Checkpoint, force: bool) {
+        if let Some((path, every)) = &self.checkpoint {
+            if force || checkpoint.iteration.is_multiple_of(*every) {
+                if let Err(e) = checkpoint.save(path) {
+                    println!("Could not write checkpoint to {}: {}", path.display(), e);
+                }
             }
-            let slope = (evaluation_error) / nonce;
-            // add the absolute value since the gradient always points in the direction of greatest ASCENT
-            out.push(slope.abs());
         }
-        out
     }
-    // local search optimization routine
-    fn local_search(&self, k: f64, initial_guess: Vec<f64>) -> Vec<f64> {
+
+    /// gradient of the mean log loss over the batch with respect to each weight
+    pub fn gradient(&self, weights: &[f64], batch: &[&Position]) -> Vec<f64> {
+        let n_inverse = 1.0 / (batch.len().max(1) as f64);
+        let sum = batch
+            .par_iter()
+            .map(|position| {
+                let actual: f64 = position.status.into();
+                // for a sigmoid under log loss this is all the chain rule leaves
+                let error = better_sigmoid(dot(weights, &position.param_values)) - actual;
+                position
+                    .param_values
+                    .iter()
+                    .map(|value| error * value)
+                    .collect::<Vec<f64>>()
+            })
+            .reduce(
+                || vec![0.0; weights.len()],
+                |a, b| a.iter().zip(b).map(|(a, b)| a + b).collect(),
+            );
+        sum.into_iter().map(|slope| slope * n_inverse).collect()
+    }
+
+    /// mean cross entropy between the plain sigmoid of the score and the result
+    pub fn log_loss(&self, weights: &[f64], batch: &[Position]) -> f64 {
+        let n_inverse = 1.0 / (batch.len().max(1) as f64);
+        let sum: f64 = batch
+            .par_iter()
+            .map(|position| {
+                let actual: f64 = position.status.into();
+                // clamp so a confidently wrong position doesnt give an infinite loss
+                let predicted =
+                    better_sigmoid(dot(weights, &position.param_values)).clamp(1e-12, 1.0 - 1e-12);
+                -(actual * predicted.ln() + (1.0 - actual) * (1.0 - predicted).ln())
+            })
+            .sum();
+        n_inverse * sum
+    }
+    /// texel tuning: nudge one parameter at a time while it lowers the squared error
+    /// every parameter has its own step, which doubles when a nudge helps and halves when neither direction does
+    /// carries on from the checkpoint, which is returned with where it ended up
+    pub fn local_search(&self, checkpoint: Checkpoint) -> Checkpoint {
+        let mut c = checkpoint;
+        let k = c.k;
         // number of parameters
-        let n_params = initial_guess.len();
+        let n_params = c.weights.len();
         // lowest error so far, initialized as the current error
-        let mut best_e = self.evaluation_error(k, &initial_guess);
-        // best parameter values so far
-        let mut best_par_values = initial_guess;
-        // have we improved this iteration
-        let mut improved = true;
-        // iteration count
-        let mut x = 0;
-        while improved {
-            // assue we wont improve
-            improved = false;
+        let mut best_e = self.evaluation_error(k, &c.weights);
+        // how far each parameter gets nudged
+        if c.steps.len() != n_params {
+            c.steps = vec![self.initial_step; n_params];
+        }
+        // keep going while any parameter still has a step worth trying
+        while c.steps.iter().any(|step| *step >= self.min_step) && c.iteration < self.max_iterations
+        {
             // go through all parameters
             for pi in 0..n_params {
-                // make a new parameter value vector
-                let mut new_par_values = best_par_values.clone();
-                // increment it
-                new_par_values[pi] += 1.0;
-                // calculate the mse across the dataset
-                let mut new_e = self.evaluation_error(k, &new_par_values);
-                // if the new error is less than the current error, update the error and param valeus
-                if new_e < best_e {
-                    best_e = new_e;
-                    best_par_values = new_par_values;
-                    improved = true;
-                } else {
-                    // if it isnt, subtract it by two ( + 1 - 2 has the effect of reducing the current parameter by one)
-                    new_par_values[pi] -= 2.0;
-                    // find the new error
-                    new_e = self.evaluation_error(k, &new_par_values);
+                if c.steps[pi] < self.min_step {
+                    continue;
+                }
+                let mut improved = false;
+                for direction in [1.0, -1.0] {
+                    // make a new parameter value vector, nudged one way
+                    let mut new_par_values = c.weights.clone();
+                    new_par_values[pi] += direction * c.steps[pi];
+                    // calculate the mse across the dataset
+                    let new_e = self.evaluation_error(k, &new_par_values);
+                    // if the new error is less than the current error, update the error and param valeus
                     if new_e < best_e {
-                        // if the new error is less than the current error, update the error and param values
                         best_e = new_e;
-                        best_par_values = new_par_values;
+                        c.weights = new_par_values;
                         improved = true;
+                        break;
                     }
                 }
+                // take bigger steps while they keep paying off, and smaller ones once they overshoot
+                if improved {
+                    // capped so a parameter the data barely pins down cant run off to infinity
+                    c.steps[pi] = (c.steps[pi] * 2.0).min(self.initial_step * 8.0);
+                } else {
+                    c.steps[pi] /= 2.0;
+                }
             }
-            if x % 10 == 0 {
+            if c.iteration.is_multiple_of(10) {
                 // print every 10 iterations to prevent terminal spam
-                println!("error: {}, best_params : {:?}", best_e, best_par_values);
+                println!("error: {}, best_params : {:?}", best_e, c.weights);
+            }
+            c.iteration += 1;
+            c.loss_history.push(best_e);
+            self.save_checkpoint(&c, false);
+            // a pass over every parameter counts as an epoch
+            if !self.validation.is_empty()
+                && c.validate(
+                    self.texel_error(k, &c.weights, &self.validation),
+                    self.patience,
+                )
+            {
+                println!(
+                    "Validation error stopped improving after {} passes",
+                    c.iteration
+                );
+                break;
             }
-            x += 1;
         }
         // print the final error so that we have an idea of just how close it is now
-        print!("Final error: {} ", best_e);
-        best_par_values
+        println!("Final error: {} ", best_e);
+        self.save_checkpoint(&c, true);
+        c
     }
 
     /// routine to minimize the k value for your evaluation.
-    /// K is a scaling value used ONLY IN LOCAL SEARCH
+    /// K is a scaling value used ONLY IN TEXEL TUNING
     /// you dont need to update this every time you tune, only once.
     /// DO THIS BEFORE YOU START TUNING
     /// ITS VERY IMPORTANT
-    /// you can start the scaling value at 0
-    /// It optimizes k using localsearch.
-    pub fn minimize_k(&self, start: f64, params: &Vec<f64>) -> f64 {
-        // best k
-        let mut best = start;
-        // best error
-        let mut best_e = self.evaluation_error(start, &params);
-        // have we improved this iteration
-        let mut improved = true;
+    /// the error is assumed to have a single minimum between low and high,
+    /// which golden section search closes in on
+    pub fn minimize_k(&self, low: f64, high: f64, params: &[f64]) -> f64 {
+        // 1 / golden ratio, how much of the interval is kept every iteration
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        let (mut low, mut high) = (low.min(high), low.max(high));
+        // the two probes inside the interval, and their errors
+        let mut left = high - ratio * (high - low);
+        let mut right = low + ratio * (high - low);
+        let mut left_e = self.evaluation_error(left, params);
+        let mut right_e = self.evaluation_error(right, params);
         // iteration count
         let mut x = 0;
-        while improved {
-            // assume we wont improve
-            improved = false;
-            // set the new k
-            let mut new_k = best + 0.001;
-            // find the mse using the new k
-            let mut new_e = self.evaluation_error(new_k, &params);
-            if new_e < best_e {
-                // if the error is lower, update best_e
-                best_e = new_e;
-                // update best k
-                best = new_k;
-                // we have improved
-                improved = true;
+        while high - low > 1e-6 * (1.0 + low.abs()) {
+            if left_e < right_e {
+                // the minimum is left of the right probe, which becomes the new high
+                high = right;
+                right = left;
+                right_e = left_e;
+                left = high - ratio * (high - low);
+                left_e = self.evaluation_error(left, params);
             } else {
-                // go -.001
-                new_k -= 0.002;
-                // calculate the mse using the lowered k and the parameters
-                new_e = self.evaluation_error(new_k, &params);
-                if new_e < best_e {
-                    // if the error is lower, update best_e
-                    best_e = new_e;
-                    // update best k
-                    best = new_k;
-                    // we have improved
-                    improved = true;
-                }
+                // the minimum is right of the left probe, which becomes the new low
+                low = left;
+                left = right;
+                left_e = right_e;
+                right = low + ratio * (high - low);
+                right_e = self.evaluation_error(right, params);
             }
-
             if x % 10 == 0 {
                 // print every 10 to prevent terminal spam
-                println!("error: {}, best_k : {:?}", best_e, best);
+                println!("error: {}, k in [{}, {}]", left_e.min(right_e), low, high);
             }
             // update iter count
             x += 1;
         }
-        best
+        (low + high) / 2.0
     }
     // find the evaluation error given a k value and values
-    // this function is only used in local search
-    fn evaluation_error(&self, k: f64, values: &Vec<f64>) -> f64 {
+    // this function is only used in texel tuning
+    pub fn evaluation_error(&self, k: f64, values: &[f64]) -> f64 {
+        self.texel_error(k, values, &self.positions)
+    }
+
+    /// mean squared error of the k scaled sigmoid over the batch
+    pub fn texel_error(&self, k: f64, values: &[f64], batch: &[Position]) -> f64 {
         // total number of positions
-        let n = self.positions.len();
+        let n = batch.len().max(1);
 
         // the inverse of the number of positions
         let n_inverse = 1.0 / (n as f64);
 
         // sum of all of the squared errors
-        let sum: f64 = self
-            .positions
+        let sum: f64 = batch
             .par_iter()
             .map(|position| {
                 // calculate the score for the given position
@@ -229,7 +394,7 @@ impl Optimizer {
                 // find the actual value of the position
                 let actual: f64 = position.status.into();
                 // return the squared error
-                (actual - sigmoid(k, score as f64))
+                (actual - sigmoid(k, score)).powi(2)
             })
             .sum();
         // sum / n_positions
@@ -238,7 +403,7 @@ impl Optimizer {
 
     // uses normal sigmoid with no scaling factor
     // used in the perceptron optimizer
-    pub fn average_error(&self, values: &Vec<f64>, batch: &[Position]) -> f64 {
+    pub fn average_error(&self, values: &[f64], batch: &[Position]) -> f64 {
         // number of positions
         let n = batch.len();
         // inverse of number of positions
@@ -269,7 +434,28 @@ impl Optimizer {
         // sum / n_positions
         n_inverse * sum
     }
-    pub fn MSE_loss(&self, values: &Vec<f64>, batch: &[Position]) -> f64 {
+    /// fraction of decisive positions where the evaluation picks the winner
+    pub fn accuracy(&self, values: &[f64], batch: &[Position]) -> f64 {
+        let decisive: Vec<&Position> = batch
+            .iter()
+            .filter(|position| position.status != Status::Draw)
+            .collect();
+        let correct = decisive
+            .par_iter()
+            .filter(|position| {
+                // score of the position
+                let score: f64 = position
+                    .param_values
+                    .iter()
+                    .enumerate()
+                    .map(|x| *x.1 * values[x.0])
+                    .sum();
+                (score > 0.0) == (position.status == Status::Win)
+            })
+            .count();
+        correct as f64 / decisive.len().max(1) as f64
+    }
+    pub fn MSE_loss(&self, values: &[f64], batch: &[Position]) -> f64 {
         // number of positions
         let n = batch.len();
         // inverse of number of positions
@@ -310,7 +496,150 @@ fn sigmoid(k: f64, score: f64) -> f64 {
 fn better_sigmoid(value: f64) -> f64 {
     1.0 / (1.0 + E.powf(-value))
 }
-// transfer derivative of sigmoid
-fn transfer_derivative(value: f64) -> f64 {
-    value * (1.0 - value)
+// score of a position under the weights
+fn dot(weights: &[f64], values: &[f64]) -> f64 {
+    weights.iter().zip(values).map(|(w, v)| w * v).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use rand::rngs::StdRng;
+    use std::{env, fs, process};
+
+    const TRUE_WEIGHTS: [f64; 5] = [1.0, -2.0, 0.5, 0.0, 1.5];
+
+    /// positions with random parameter values whose results are drawn from the true weights
+    fn synthetic(count: usize) -> Vec<Position> {
+        let mut rng = StdRng::seed_from_u64(42);
+        (0..count)
+            .map(|_| {
+                let param_values = TRUE_WEIGHTS
+                    .iter()
+                    .map(|_| rng.gen_range(-1.0..1.0))
+                    .collect::<Vec<f64>>();
+                let win = rng.gen_bool(better_sigmoid(dot(&TRUE_WEIGHTS, &param_values)));
+                Position {
+                    status: if win { Status::Win } else { Status::Loss },
+                    board: Board {
+                        snakes: vec![],
+                        food: vec![],
+                        width: 11,
+                        height: 11,
+                        hazards: vec![],
+                    },
+                    health: vec![],
+                    future_param_values: param_values.clone(),
+                    param_values,
+                    turn: 0,
+                    game_id: String::new(),
+                }
+            })
+            .collect()
+    }
+
+    fn assert_close(weights: &[f64]) {
+        for (found, expected) in weights.iter().zip(TRUE_WEIGHTS) {
+            assert!(
+                (found - expected).abs() < 0.25,
+                "found {:?}, expected {:?}",
+                weights,
+                TRUE_WEIGHTS
+            );
+        }
+    }
+
+    #[test]
+    fn gradient_matches_finite_difference() {
+        let optimizer = Optimizer::new(synthetic(500));
+        let weights = vec![0.3, -0.1, 0.7, 0.2, -0.4];
+        let batch = optimizer.positions.iter().collect::<Vec<&Position>>();
+        let gradient = optimizer.gradient(&weights, &batch);
+        let nonce = 1e-6;
+        for x in 0..weights.len() {
+            let mut up = weights.clone();
+            up[x] += nonce;
+            let mut down = weights.clone();
+            down[x] -= nonce;
+            let slope = (optimizer.log_loss(&up, &optimizer.positions)
+                - optimizer.log_loss(&down, &optimizer.positions))
+                / (2.0 * nonce);
+            assert!((slope - gradient[x]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn sgd_with_momentum_finds_true_weights() {
+        let weights = Optimizer::new(synthetic(20_000))
+            .with_learning_rate(0.05)
+            .with_batch_size(500)
+            .with_max_iterations(5_000)
+            .with_update(Update::Momentum(0.9))
+            .stoch_gradient_descent(Checkpoint::new("sgd", vec![0.0; TRUE_WEIGHTS.len()]));
+        assert_close(weights.final_weights());
+    }
+
+    #[test]
+    fn resumes_from_a_saved_checkpoint() {
+        let path = env::temp_dir().join(format!("paramoptimizer-resume-{}.json", process::id()));
+        let optimizer = |iterations| {
+            Optimizer::new(synthetic(5_000))
+                .with_learning_rate(0.05)
+                .with_batch_size(500)
+                .with_max_iterations(iterations)
+                .with_update(Update::adam())
+                .with_checkpoints(path.clone(), 100)
+        };
+        let first = optimizer(300)
+            .stoch_gradient_descent(Checkpoint::new("adam", vec![0.0; TRUE_WEIGHTS.len()]));
+        let saved = Checkpoint::load(&path).unwrap();
+        assert!(!path.with_extension("tmp").exists());
+        assert_eq!(saved.iteration, 300);
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12);
+        assert!(close(&saved.weights, &first.weights));
+        assert!(close(&saved.velocity, &first.velocity));
+        assert!(close(&saved.squared, &first.squared));
+        assert!(close(&saved.loss_history, &first.loss_history));
+
+        // carrying on keeps the history and ends up where a single run would
+        let resumed = optimizer(5_000).stoch_gradient_descent(saved);
+        assert!(resumed.iteration > 300);
+        assert!(resumed.loss_history.len() > first.loss_history.len());
+        assert!(close(
+            &resumed.loss_history[..first.loss_history.len()],
+            &first.loss_history
+        ));
+        assert_close(resumed.final_weights());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn golden_section_finds_k() {
+        // the plain sigmoid is the k scaled one with k = 400 / ln(10)
+        let optimizer = Optimizer::new(synthetic(20_000));
+        let k = optimizer.minimize_k(0.0, 1000.0, &TRUE_WEIGHTS);
+        assert!((k - 400.0 / 10f64.ln()).abs() < 20.0, "found k {}", k);
+    }
+
+    #[test]
+    fn texel_tuning_finds_true_weights() {
+        let mut checkpoint = Checkpoint::new("texel", vec![0.0; TRUE_WEIGHTS.len()]);
+        checkpoint.k = 400.0 / 10f64.ln();
+        let weights = Optimizer::new(synthetic(20_000))
+            .with_steps(0.5, 1e-3)
+            .local_search(checkpoint);
+        assert_close(weights.final_weights());
+    }
+
+    #[test]
+    fn adam_finds_true_weights() {
+        let weights = Optimizer::new(synthetic(20_000))
+            .with_learning_rate(0.05)
+            .with_batch_size(500)
+            .with_max_iterations(5_000)
+            .with_update(Update::adam())
+            .stoch_gradient_descent(Checkpoint::new("sgd", vec![0.0; TRUE_WEIGHTS.len()]));
+        assert_close(weights.final_weights());
+    }
 }
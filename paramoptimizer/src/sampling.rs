@@ -0,0 +1,32 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use sampler::{Outcome, Sample};
+
+use crate::board::{Position, Status};
+
+impl Sample for Position {
+    fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        for snake in &self.board.snakes {
+            snake.body.hash(&mut hasher);
+        }
+        let mut food = self.board.food.clone();
+        food.sort_by_key(|c| (c.x, c.y));
+        food.hash(&mut hasher);
+        self.health.hash(&mut hasher);
+        hasher.finish()
+    }
+    fn turn(&self) -> u32 {
+        self.turn
+    }
+    fn outcome(&self) -> Outcome {
+        match self.status {
+            Status::Win => Outcome::Win,
+            Status::Loss => Outcome::Loss,
+            Status::Draw => Outcome::Draw,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::board::{Battlesnake, Position};
+
+/// positions divided up so that every game ends up in exactly one part
+pub struct Split {
+    pub train: Vec<Position>,
+    pub validation: Vec<Position>,
+    pub test: Vec<Position>,
+}
+
+/// give every position the index of the game it came from
+/// older datastores have no game ids or turns, but they kept every game's positions together in turn order,
+/// so a new game is started whenever the first snake changes or didnt just move out of its last position
+fn game_indices(positions: &[Position]) -> Vec<usize> {
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut unnamed = 0;
+    let mut last: Option<&Battlesnake> = None;
+    positions
+        .iter()
+        .map(|position| {
+            if position.game_id.is_empty() {
+                let me = &position.board.snakes[0];
+                // a turn later my neck is where my head was
+                let same_game =
+                    last.is_some_and(|before| before.id == me.id && me.body[1] == before.body[0]);
+                if last.is_some() && !same_game {
+                    unnamed += 1;
+                }
+                last = Some(me);
+                // keep clear of the indices the named games get
+                usize::MAX - unnamed
+            } else {
+                let next = ids.len();
+                *ids.entry(&position.game_id).or_insert(next)
+            }
+        })
+        .collect()
+}
+
+/// shuffle the games with the seed, then put the first `test` fraction of them in test,
+/// the next `validation` fraction in validation and the rest in train
+pub fn split_by_game(positions: Vec<Position>, validation: f64, test: f64, seed: u64) -> Split {
+    let indices = game_indices(&positions);
+    let mut games = indices.clone();
+    games.sort_unstable();
+    games.dedup();
+    games.shuffle(&mut StdRng::seed_from_u64(seed));
+
+    let test_games = (games.len() as f64 * test).round() as usize;
+    let validation_games = (games.len() as f64 * validation).round() as usize;
+    // 0 is test, 1 is validation and 2 is train
+    let part: HashMap<usize, u8> = games
+        .iter()
+        .enumerate()
+        .map(|(order, game)| {
+            let part = if order < test_games {
+                0
+            } else if order < test_games + validation_games {
+                1
+            } else {
+                2
+            };
+            (*game, part)
+        })
+        .collect();
+
+    let mut split = Split {
+        train: vec![],
+        validation: vec![],
+        test: vec![],
+    };
+    for (position, game) in positions.into_iter().zip(indices) {
+        match part[&game] {
+            0 => split.test.push(position),
+            1 => split.validation.push(position),
+            _ => split.train.push(position),
+        }
+    }
+    split
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datastore;
+    use std::path::Path;
+
+    #[test]
+    fn baseline_datastore_splits_into_its_games() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/baseline_datastore");
+        let (_, frames) = datastore::load(&path).unwrap();
+        let mut games = game_indices(&frames);
+        games.dedup();
+        // the positions of every game are next to each other
+        assert_eq!(games.len(), 3);
+    }
+
+    #[test]
+    fn games_without_ids_stay_in_one_part() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/unnamed_datastore");
+        let (_, frames) = datastore::load(&path).unwrap();
+        let games = |positions: &[Position]| {
+            let mut games = game_indices(positions);
+            games.dedup();
+            games.len()
+        };
+        assert_eq!(games(&frames), 3);
+        let split = split_by_game(frames, 0.34, 0.34, 7);
+        // a game cut in two would show up as an extra game in each part it landed in
+        for part in [&split.train, &split.validation, &split.test] {
+            assert_eq!(games(part), 1);
+        }
+    }
+}
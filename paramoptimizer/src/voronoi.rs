@@ -0,0 +1,101 @@
+use crate::board::{Board, Coordinate};
+
+/// who gets to every square first, from one breadth first search out of every head at once
+pub struct Voronoi {
+    /// steps from the closest head, none if no head can reach the square
+    pub distance: Vec<Option<u32>>,
+    /// the only snake that gets there first, none when two or more tie or nobody gets there
+    pub owner: Vec<Option<usize>>,
+}
+
+impl Voronoi {
+    /// search out from every head over the squares no snake is on
+    pub fn new(board: &Board, occupied: &[bool]) -> Self {
+        let heads = board
+            .snakes
+            .iter()
+            .map(|snake| snake.body[0])
+            .collect::<Vec<Coordinate>>();
+        Self::from_starts(board, occupied, &heads)
+    }
+
+    /// search out from the given squares at once, owners are indices into `starts`
+    pub fn from_starts(board: &Board, occupied: &[bool], starts: &[Coordinate]) -> Self {
+        let squares = (board.width * board.height) as usize;
+        let mut distance = vec![None; squares];
+        let mut owner = vec![None; squares];
+        let mut frontier = vec![];
+        for (idx, start) in starts.iter().enumerate() {
+            if !board.in_bounds(start) {
+                continue;
+            }
+            let square = board.index(start);
+            if distance[square].is_some() {
+                // two heads on one square, neither owns it
+                owner[square] = None;
+                continue;
+            }
+            distance[square] = Some(0);
+            owner[square] = Some(idx);
+            frontier.push(*start);
+        }
+
+        let mut steps = 0;
+        while !frontier.is_empty() {
+            steps += 1;
+            let mut next = vec![];
+            for square in frontier {
+                let from = owner[board.index(&square)];
+                for neighbour in neighbours(&square) {
+                    if !board.in_bounds(&neighbour) || occupied[board.index(&neighbour)] {
+                        continue;
+                    }
+                    let idx = board.index(&neighbour);
+                    match distance[idx] {
+                        None => {
+                            distance[idx] = Some(steps);
+                            owner[idx] = from;
+                            next.push(neighbour);
+                        }
+                        // reached this layer by someone else as well, so it is contested
+                        Some(d) if d == steps && owner[idx] != from => owner[idx] = None,
+                        _ => {}
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Self { distance, owner }
+    }
+
+    /// the snake that owns the square, if any one does
+    pub fn owner_of(&self, board: &Board, c: &Coordinate) -> Option<usize> {
+        if !board.in_bounds(c) {
+            return None;
+        }
+        self.owner[board.index(c)]
+    }
+
+    /// steps to the square from the closest start, none if its out of bounds or out of reach
+    pub fn distance_to(&self, board: &Board, c: &Coordinate) -> Option<u32> {
+        if !board.in_bounds(c) {
+            return None;
+        }
+        self.distance[board.index(c)]
+    }
+
+    /// how many squares were reached, the starts included
+    pub fn reached(&self) -> usize {
+        self.distance.iter().filter(|d| d.is_some()).count()
+    }
+}
+
+/// the four squares next to a square, some of them may be off the board
+pub fn neighbours(c: &Coordinate) -> [Coordinate; 4] {
+    [
+        Coordinate::new(c.x, c.y + 1),
+        Coordinate::new(c.x, c.y - 1),
+        Coordinate::new(c.x - 1, c.y),
+        Coordinate::new(c.x + 1, c.y),
+    ]
+}
@@ -1,19 +1,62 @@
-mod board;
-mod cartprod;
-mod coordinate;
-mod genmove;
-mod perft;
-mod snake;
-use std::fs;
+use clap::Parser;
+use datagen::{http_player::read_request, validate::validate};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+};
 
+/// check saved move requests for positions that cant happen in a real game
+#[derive(Parser)]
 struct Args {
+    /// a json move request, or a directory to check every file under
     #[clap(short, long, value_parser)]
     path: String,
 }
+
 fn main() {
-    let paths = fs::read_dir("./").unwrap();
+    let args = Args::parse();
+    let mut files = vec![];
+    if let Err(e) = collect_files(Path::new(&args.path), &mut files) {
+        eprintln!("could not read {}: {}", args.path, e);
+        process::exit(1);
+    }
+    files.sort();
 
-    for path in paths {
-        println!("Name: {}", path.unwrap().path().display())
+    let mut invalid = 0;
+    for path in &files {
+        let errors = match check(path) {
+            Ok(()) => continue,
+            Err(errors) => errors,
+        };
+        invalid += 1;
+        println!("{}:", path.display());
+        for error in errors {
+            println!("    {}", error);
+        }
+    }
+    println!("{} of {} files invalid", invalid, files.len());
+    if invalid > 0 {
+        process::exit(1);
     }
 }
+
+/// every file at or under the path
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        collect_files(&entry?.path(), files)?;
+    }
+    Ok(())
+}
+
+/// read the request in the file and validate it, every problem found as a message
+fn check(path: &Path) -> Result<(), Vec<String>> {
+    let contents = fs::read_to_string(path).map_err(|e| vec![e.to_string()])?;
+    let (board, ruleset) = read_request(&contents).map_err(|e| vec![e.to_string()])?;
+    validate(&board, ruleset == "wrapped")
+        .map_err(|errors| errors.iter().map(|e| e.to_string()).collect())
+}
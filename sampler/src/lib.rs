@@ -0,0 +1,239 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use clap::Args;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+/// how the game went for the snake a position is labeled for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// what the sampler needs to know about a training position
+pub trait Sample {
+    /// equal for positions that are the same as far as training is concerned
+    fn key(&self) -> u64;
+    fn turn(&self) -> u32;
+    fn outcome(&self) -> Outcome;
+}
+
+/// thins out a dataset so that common positions dont drown out everything else
+#[derive(Clone, Copy, Default)]
+pub struct Sampler {
+    dedup: bool,
+    turn_buckets: Option<(u32, usize)>,
+    balance: bool,
+    seed: u64,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// keep only the first of any positions with the same key
+    pub fn with_dedup(mut self) -> Self {
+        self.dedup = true;
+        self
+    }
+
+    /// group positions into buckets of `size` turns and keep at most `max` random ones from each
+    pub fn with_turn_buckets(mut self, size: u32, max: usize) -> Self {
+        self.turn_buckets = Some((size.max(1), max));
+        self
+    }
+
+    /// keep as many wins as losses, and no more draws than that
+    /// data without any wins or without any losses is left as it is
+    pub fn with_balance(mut self) -> Self {
+        self.balance = true;
+        self
+    }
+
+    /// seed for which positions the random stages keep, the same seed and input always keep the same ones
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// apply every stage in order: dedup, turn buckets, then balancing
+    pub fn apply<T: Sample>(&self, items: Vec<T>) -> Vec<T> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut items = items;
+        if self.dedup {
+            let mut seen = HashSet::new();
+            items.retain(|item| seen.insert(item.key()));
+        }
+        if let Some((size, max)) = self.turn_buckets {
+            items = cap_groups(items, max, &mut rng, |item| item.turn() / size);
+        }
+        if self.balance {
+            let count = |outcome| items.iter().filter(|i| i.outcome() == outcome).count();
+            let max = count(Outcome::Win).min(count(Outcome::Loss));
+            // one sided data has nothing to even out against, and capping at 0 would drop it all
+            if max > 0 {
+                items = cap_groups(items, max, &mut rng, |item| item.outcome());
+            }
+        }
+        items
+    }
+}
+
+/// keep at most max random items with each group, leaving the survivors in their original order
+fn cap_groups<T, G, F>(items: Vec<T>, max: usize, rng: &mut StdRng, group: F) -> Vec<T>
+where
+    G: Hash + Eq,
+    F: Fn(&T) -> G,
+{
+    let mut groups: HashMap<G, Vec<usize>> = HashMap::new();
+    for (idx, item) in items.iter().enumerate() {
+        groups.entry(group(item)).or_default().push(idx);
+    }
+    let mut keep = vec![false; items.len()];
+    // sort the groups so that a seeded run always shuffles them in the same order
+    let mut groups = groups.into_values().collect::<Vec<Vec<usize>>>();
+    groups.sort_unstable_by_key(|g| g[0]);
+    for mut members in groups {
+        members.shuffle(rng);
+        for idx in members.into_iter().take(max) {
+            keep[idx] = true;
+        }
+    }
+    items
+        .into_iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(item, _)| item)
+        .collect()
+}
+
+/// the sampler's command line flags, for every tool that writes training positions
+#[derive(Args)]
+pub struct SampleArgs {
+    /// drop repeats of the same position
+    #[clap(long, value_parser)]
+    pub dedup: bool,
+    /// size in turns of the buckets --max-per-bucket applies to
+    #[clap(long, value_parser, default_value_t = 10)]
+    pub turn_bucket: u32,
+    /// keep at most this many random positions from each turn bucket
+    #[clap(long, value_parser)]
+    pub max_per_bucket: Option<usize>,
+    /// keep as many wins as losses, and no more draws than that, unless there are no wins or no losses
+    #[clap(long, value_parser)]
+    pub balance: bool,
+    /// seed for the random choices made while sampling
+    #[clap(long, value_parser, default_value_t = 0)]
+    pub sample_seed: u64,
+}
+
+impl SampleArgs {
+    pub fn sampler(&self) -> Sampler {
+        let mut sampler = Sampler::new().with_seed(self.sample_seed);
+        if self.dedup {
+            sampler = sampler.with_dedup();
+        }
+        if let Some(max) = self.max_per_bucket {
+            sampler = sampler.with_turn_buckets(self.turn_bucket, max);
+        }
+        if self.balance {
+            sampler = sampler.with_balance();
+        }
+        sampler
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item {
+        key: u64,
+        turn: u32,
+        outcome: Outcome,
+    }
+
+    impl Sample for Item {
+        fn key(&self) -> u64 {
+            self.key
+        }
+        fn turn(&self) -> u32 {
+            self.turn
+        }
+        fn outcome(&self) -> Outcome {
+            self.outcome
+        }
+    }
+
+    fn items() -> Vec<Item> {
+        (0..500)
+            .map(|i| Item {
+                key: i % 400,
+                turn: i as u32 % 97,
+                outcome: [Outcome::Win, Outcome::Win, Outcome::Loss, Outcome::Draw][i as usize % 4],
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_keeps_the_same_positions() {
+        let sampler = Sampler::new()
+            .with_dedup()
+            .with_turn_buckets(10, 20)
+            .with_balance();
+        let keys = |sampler: Sampler| {
+            sampler
+                .apply(items())
+                .iter()
+                .map(|i| i.key)
+                .collect::<Vec<u64>>()
+        };
+        assert_eq!(keys(sampler), keys(sampler));
+        assert_ne!(keys(sampler), keys(sampler.with_seed(1)));
+    }
+
+    #[test]
+    fn balance_evens_out_wins_and_losses() {
+        let kept = Sampler::new().with_balance().apply(items());
+        let count = |outcome| kept.iter().filter(|i| i.outcome == outcome).count();
+        assert_eq!(count(Outcome::Win), count(Outcome::Loss));
+        assert!(count(Outcome::Draw) <= count(Outcome::Loss));
+    }
+
+    #[test]
+    fn balance_leaves_one_sided_data_alone() {
+        let one_sided = |outcome: Outcome| {
+            items()
+                .into_iter()
+                .map(|i| Item {
+                    outcome: if i.outcome == outcome {
+                        Outcome::Draw
+                    } else {
+                        i.outcome
+                    },
+                    ..i
+                })
+                .collect::<Vec<Item>>()
+        };
+        // no losses, then no wins
+        assert_eq!(
+            Sampler::new()
+                .with_balance()
+                .apply(one_sided(Outcome::Loss))
+                .len(),
+            500
+        );
+        assert_eq!(
+            Sampler::new()
+                .with_balance()
+                .apply(one_sided(Outcome::Win))
+                .len(),
+            500
+        );
+    }
+}